@@ -12,6 +12,17 @@ static DB: LazyLock<OnceCell<Arc<Surreal<Db>>>> = LazyLock::new(|| OnceCell::new
 
 pub async fn init_db(path: PathBuf) -> Result<()> {
     let db = Surreal::new::<SurrealKv>(path).await?;
+    setup(db).await
+}
+
+/// 内存数据库，供测试使用
+#[cfg(test)]
+pub async fn init_mem_db() -> Result<()> {
+    let db = Surreal::new::<surrealdb::engine::local::Mem>(()).await?;
+    setup(db).await
+}
+
+async fn setup(db: Surreal<Db>) -> Result<()> {
     db.use_ns("app").use_db("app").await?;
 
     // let _ = db.query(QueryKind::InitAccess.as_str()).await?;
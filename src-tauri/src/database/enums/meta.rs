@@ -6,6 +6,12 @@ use specta::Type;
 pub enum MetaKey {
     SaveDir,
     FirstLaunch,
+    /// 持久化的暂停范围，JSON 数组
+    PausedScopes,
+    /// GraphQL query id 与 features 的覆盖，JSON 对象
+    GraphqlOverrides,
+    /// 出站代理设置，JSON 对象
+    ProxySettings,
 }
 
 impl MetaKey {
@@ -13,6 +19,9 @@ impl MetaKey {
         match self {
             MetaKey::SaveDir => "save_dir",
             MetaKey::FirstLaunch => "first_launch",
+            MetaKey::PausedScopes => "paused_scopes",
+            MetaKey::GraphqlOverrides => "graphql_overrides",
+            MetaKey::ProxySettings => "proxy_settings",
         }
     }
 
@@ -20,6 +29,9 @@ impl MetaKey {
         match s {
             "save_dir" => Ok(MetaKey::SaveDir),
             "first_launch" => Ok(MetaKey::FirstLaunch),
+            "paused_scopes" => Ok(MetaKey::PausedScopes),
+            "graphql_overrides" => Ok(MetaKey::GraphqlOverrides),
+            "proxy_settings" => Ok(MetaKey::ProxySettings),
             _ => Err(format!("Unknown MetaKey: {}", s)),
         }
     }
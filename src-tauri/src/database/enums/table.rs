@@ -7,6 +7,14 @@ pub trait TableName {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Table {
     LikedPost,
+    Bookmark,
+    ListSource,
+    ListPost,
+    SavedSearch,
+    SearchPost,
+    FollowSnapshot,
+    PostCheck,
+    Space,
     Media,
     Post,
     Reply,
@@ -24,6 +32,14 @@ impl Table {
     pub const fn as_str(&self) -> &'static str {
         match self {
             Table::LikedPost => "liked_post",
+            Table::Bookmark => "bookmark",
+            Table::ListSource => "list_source",
+            Table::ListPost => "list_post",
+            Table::SavedSearch => "saved_search",
+            Table::SearchPost => "search_post",
+            Table::FollowSnapshot => "follow_snapshot",
+            Table::PostCheck => "post_check",
+            Table::Space => "space",
             Table::Media => "media",
             Table::Post => "post",
             Table::Reply => "reply",
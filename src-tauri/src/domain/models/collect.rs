@@ -40,6 +40,7 @@ impl Collection {
         for p in db_posts {
             items.push(p.into_domain().await.unwrap());
         }
+        Post::fill_spaces(items.iter_mut()).await?;
         Ok(Self { name, items })
     }
 
@@ -64,6 +65,7 @@ impl Collection {
         for p in db_posts {
             items.push(p.into_domain().await.unwrap());
         }
+        Post::fill_spaces(items.iter_mut()).await?;
         let cursor = posts_ids.cursor;
 
         Ok(Chunk {
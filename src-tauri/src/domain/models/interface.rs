@@ -1,7 +1,13 @@
+use std::future::Future;
+
 use serde::{Deserialize, Serialize};
 use specta::Type;
 
+use super::twitter::bookmark::BookmarkedPost;
 use super::twitter::like::LikedPost;
+use super::twitter::list::ListedPost;
+use super::twitter::search::SearchedPost;
+use crate::domain::platform::twitter::api::models::TimelineItem;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
 pub struct Chunk<T> {
@@ -9,23 +15,77 @@ pub struct Chunk<T> {
     pub data: Vec<T>,
 }
 
-#[tauri::command]
-#[specta::specta]
-pub async fn take_post_chunk(cursor: Option<String>) -> Result<Chunk<LikedPost>, String> {
-    let data = LikedPost::select_pagin(100, cursor.map(|c| c.parse::<u32>().unwrap()))
-        .await
+/// 按账号筛选时传入账号 id，为空时返回全部账号的记录
+fn parse_account(account: Option<String>) -> Result<Option<i64>, String> {
+    account
+        .map(|a| a.parse::<i64>())
+        .transpose()
+        .map_err(|e| e.to_string())
+}
+
+/// 各类帖子分页共用：游标即上一页最后一条的 sortidx，取到空页时报错
+async fn take_chunk<T, F, Fut>(cursor: Option<String>, select: F) -> Result<Chunk<T>, String>
+where
+    T: TimelineItem,
+    F: FnOnce(Option<u32>) -> Fut,
+    Fut: Future<Output = anyhow::Result<Vec<T>>>,
+{
+    let cursor = cursor
+        .map(|c| c.parse::<u32>())
+        .transpose()
         .map_err(|e| e.to_string())?;
+    let data = select(cursor).await.map_err(|e| e.to_string())?;
     let cursor = data
         .last()
-        .map(|p| p.sortidx.to_string())
+        .map(|p| p.sortidx().to_string())
         .ok_or_else(|| "No data found".to_string())?;
     Ok(Chunk { cursor, data })
 }
 
+#[tauri::command]
+#[specta::specta]
+pub async fn take_post_chunk(
+    cursor: Option<String>,
+    account: Option<String>,
+) -> Result<Chunk<LikedPost>, String> {
+    let account = parse_account(account)?;
+    take_chunk(cursor, |c| LikedPost::select_pagin(account, 100, c)).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn take_bookmark_chunk(
+    cursor: Option<String>,
+    account: Option<String>,
+) -> Result<Chunk<BookmarkedPost>, String> {
+    let account = parse_account(account)?;
+    take_chunk(cursor, |c| BookmarkedPost::select_pagin(account, 100, c)).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn take_list_chunk(
+    list_id: String,
+    cursor: Option<String>,
+) -> Result<Chunk<ListedPost>, String> {
+    let list_id = list_id.parse::<i64>().map_err(|e| e.to_string())?;
+    take_chunk(cursor, |c| ListedPost::select_pagin(list_id, 100, c)).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn take_search_chunk(
+    search_id: String,
+    cursor: Option<String>,
+) -> Result<Chunk<SearchedPost>, String> {
+    let search_id = search_id.parse::<i64>().map_err(|e| e.to_string())?;
+    take_chunk(cursor, |c| SearchedPost::select_pagin(search_id, 100, c)).await
+}
+
 #[tauri::command]
 #[specta::specta]
 pub async fn check_has_data() -> Result<bool, String> {
-    let data = LikedPost::select_pagin(1, None)
+    let data = LikedPost::select_pagin(None, 1, None)
         .await
         .map_err(|e| e.to_string())?;
     Ok(!data.is_empty())
@@ -133,7 +133,18 @@ impl DbAsset {
     pub async fn get(id: RecordId) -> Result<Self> {
         DbAsset::select_record(id).await
     }
+    /// 下载优先级：头像 > 缩略图 > 图片 > 视频
+    pub fn priority(&self) -> u8 {
+        match self.ty {
+            AssetType::Avatar => 3,
+            AssetType::Thumb => 2,
+            AssetType::Media if self.name.ends_with(".mp4") => 0,
+            AssetType::Media => 1,
+        }
+    }
     pub fn into_task(self, kind: TaskKind) -> Task {
+        let priority = kind.priority() + self.priority();
+        let retry_policy = kind.retry_policy();
         Task {
             id: Task::record_id(self.id.key().to_owned()),
             tar: self.id,
@@ -143,6 +154,9 @@ impl DbAsset {
             result: None,
             error: None,
             retry_count: 0,
+            priority,
+            retry_policy,
+            next_attempt_at: None,
             started_at: None,
             finished_at: None,
         }
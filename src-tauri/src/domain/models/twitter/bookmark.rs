@@ -0,0 +1,201 @@
+use crate::database::enums::table::Table;
+use crate::database::{query_raw, Crud, HasId, Order, QueryKind};
+use crate::domain::platform::TaskKind;
+use crate::utils::serialize::into_u32_from_string_or_number;
+
+use super::entities::DbEntitie;
+use super::post::{load_page, DbPost, Post, PostRecord};
+
+use crate::{impl_crud, impl_id, impl_schema};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use surrealdb::RecordId;
+
+/// 书签，排序方式与 LikedPost 相同，存放在独立的 bookmark 表
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct BookmarkedPost {
+    #[serde(deserialize_with = "into_u32_from_string_or_number")]
+    pub sortidx: u32,
+    pub post: Post,
+    /// 来自哪个账号，多账号之前抓取的记录为空
+    #[serde(default)]
+    pub account: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DbBookmark {
+    pub id: RecordId,
+    pub post: RecordId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<i64>,
+    /// 旧记录没有该字段，sortidx 就是记录 id，启动时由 `claim_unattributed` 迁移
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sortidx: Option<u32>,
+}
+
+impl_crud!(DbBookmark, Table::Bookmark);
+impl_id!(DbBookmark, id);
+impl_schema!(
+    DbBookmark,
+    r#"
+    DEFINE INDEX bookmark_account ON TABLE bookmark FIELDS account;
+    DEFINE INDEX bookmark_sortidx ON TABLE bookmark FIELDS sortidx;
+    DEFINE INDEX bookmark_account_sortidx ON TABLE bookmark FIELDS account, sortidx;
+"#
+);
+
+impl BookmarkedPost {
+    pub async fn select_pagin(
+        account: Option<i64>,
+        count: i64,
+        cursor: Option<u32>,
+    ) -> Result<Vec<Self>> {
+        let dbresult = DbBookmark::select_pagin(account, count, cursor).await?;
+        load_page(dbresult).await
+    }
+
+    pub fn from_api(json: &Value) -> Option<Self> {
+        let post = json
+            .pointer("/content/itemContent/tweet_results/result")
+            .and_then(Post::from_api)?;
+        Some(Self {
+            sortidx: 0,
+            post,
+            account: None,
+        })
+    }
+
+    pub fn into_db(self) -> DbBookmark {
+        DbBookmark::from_domain(self)
+    }
+
+    pub fn into_entities(self, task_kind: TaskKind) -> DbEntitie {
+        let bookmark = self.clone().into_db();
+        let mut entities = DbEntitie::from_post(self.post, task_kind);
+        entities.bookmark.push(bookmark);
+        entities
+    }
+}
+
+impl DbBookmark {
+    pub async fn into_domain(self) -> Result<BookmarkedPost> {
+        Ok(BookmarkedPost {
+            sortidx: self.sortidx()?,
+            post: DbPost::get(self.post).await?,
+            account: self.account.map(|a| a.to_string()),
+        })
+    }
+
+    pub fn new(post: RecordId, account: Option<i64>, sortidx: u32) -> Self {
+        Self {
+            id: DbBookmark::key(account, sortidx),
+            post,
+            account,
+            sortidx: Some(sortidx),
+        }
+    }
+
+    /// 各账号的扫描从各自的开始时间倒数 sortidx，主键带上账号才不会互相覆盖
+    pub fn key(account: Option<i64>, sortidx: u32) -> RecordId {
+        let account = account.map(|a| a.to_string()).unwrap_or_default();
+        DbBookmark::record_id(format!("{account}_{sortidx}"))
+    }
+
+    fn sortidx(&self) -> Result<u32> {
+        match self.sortidx {
+            Some(sortidx) => Ok(sortidx),
+            None => self
+                .id
+                .key()
+                .to_string()
+                .parse::<u32>()
+                .map_err(|e| anyhow::anyhow!("failed to parse sortidx: {}", e)),
+        }
+    }
+
+    pub fn from_domain(domain: BookmarkedPost) -> Self {
+        Self::new(
+            DbPost::record_id(domain.post.rest_id),
+            domain.account.and_then(|a| a.parse::<i64>().ok()),
+            domain.sortidx,
+        )
+    }
+
+    /// 多账号之前的书签都来自默认账号，补上归属，并把只有 sortidx 的旧主键换成带账号的主键
+    ///
+    /// 还没有默认账号时只迁移主键，归属留到下次启动。
+    pub async fn claim_unattributed(account: Option<i64>) -> Result<()> {
+        let sql = format!(
+            "SELECT * FROM {} WHERE account = NONE OR sortidx = NONE;",
+            Table::Bookmark
+        );
+        let mut stale = Vec::new();
+        let mut claimed = Vec::new();
+        for record in DbBookmark::query_take(&sql, None).await? {
+            let next = Self::new(
+                record.post.clone(),
+                record.account.or(account),
+                record.sortidx()?,
+            );
+            // 主键里带着账号，补上归属或迁移旧主键都会换成新的记录
+            if next.id != record.id {
+                stale.push(record.id.to_string());
+                claimed.push(next);
+            }
+        }
+        if claimed.is_empty() {
+            return Ok(());
+        }
+        DbBookmark::insert_jump(claimed).await?;
+        for chunk in stale.chunks(1_000) {
+            let sql = format!("DELETE [{}] RETURN NONE;", chunk.join(", "));
+            query_raw(&sql).await?.check()?;
+        }
+        Ok(())
+    }
+
+    /// 指定账号时只取该账号的记录，游标为上一页最后一条的 sortidx
+    pub async fn select_pagin(
+        account: Option<i64>,
+        count: i64,
+        cursor: Option<u32>,
+    ) -> Result<Vec<Self>> {
+        let sql = match account {
+            None => QueryKind::pagin(
+                Table::Bookmark,
+                count,
+                cursor.map(|c| c.to_string()),
+                Order::Desc,
+                "sortidx",
+            ),
+            Some(account) => {
+                let before = cursor
+                    .map(|c| format!(" AND sortidx < {c}"))
+                    .unwrap_or_default();
+                format!(
+                    "SELECT * FROM {} WHERE account = {account}{before} ORDER BY sortidx DESC LIMIT {count};",
+                    Table::Bookmark
+                )
+            }
+        };
+        DbBookmark::query_take(&sql, None)
+            .await
+            .map_err(|e| e.into())
+    }
+}
+
+#[async_trait::async_trait]
+impl PostRecord for DbBookmark {
+    type Domain = BookmarkedPost;
+    fn post_id(&self) -> RecordId {
+        self.post.clone()
+    }
+    async fn load(self) -> Result<BookmarkedPost> {
+        self.into_domain().await
+    }
+    fn post_mut(domain: &mut BookmarkedPost) -> &mut Post {
+        &mut domain.post
+    }
+}
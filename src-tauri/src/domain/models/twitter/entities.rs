@@ -1,14 +1,21 @@
-use crate::domain::platform::Task;
+use crate::domain::platform::{Task, TaskKind};
 
 use super::asset::DbAsset;
+use super::bookmark::DbBookmark;
 use super::like::DbLikedPost;
+use super::list::DbListPost;
 use super::media::DbMedia;
-use super::post::{DbPost, DbReply};
+use super::post::{DbPost, DbReply, Post};
+use super::search::DbSearchPost;
+use super::space::DbSpace;
 use super::users::DbUser;
 
 #[derive(Debug, Clone)]
 pub struct DbEntitie {
     pub like: Vec<DbLikedPost>,
+    pub bookmark: Vec<DbBookmark>,
+    pub list_posts: Vec<DbListPost>,
+    pub search_posts: Vec<DbSearchPost>,
     pub posts: Vec<DbPost>,
     pub medias: Vec<DbMedia>,
     pub users: Vec<DbUser>,
@@ -21,6 +28,9 @@ impl DbEntitie {
     pub fn default() -> Self {
         Self {
             like: Vec::new(),
+            bookmark: Vec::new(),
+            list_posts: Vec::new(),
+            search_posts: Vec::new(),
             posts: Vec::new(),
             medias: Vec::new(),
             users: Vec::new(),
@@ -34,6 +44,9 @@ impl DbEntitie {
         let mut new = DbEntitie::default();
         for e in list {
             new.like.extend(e.like);
+            new.bookmark.extend(e.bookmark);
+            new.list_posts.extend(e.list_posts);
+            new.search_posts.extend(e.search_posts);
             new.posts.extend(e.posts);
             new.medias.extend(e.medias);
             new.users.extend(e.users);
@@ -43,4 +56,119 @@ impl DbEntitie {
         }
         new
     }
+
+    /// 一条推文及其引用、回复中涉及的全部实体，不含 like / bookmark / 列表 / 搜索的排序记录
+    pub fn from_post(post: Post, task_kind: TaskKind) -> DbEntitie {
+        let mut posts = Vec::new();
+        posts.push(post.clone().into_db());
+        if let Some(quote) = post.quote.clone() {
+            posts.push(quote.into_db());
+        }
+
+        let mut medias = post
+            .media
+            .clone()
+            .map(|list| list.into_iter().map(|m| m.into_db()).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let replies = post
+            .replies
+            .clone()
+            .map(|list| {
+                list.into_iter()
+                    .flat_map(|c| {
+                        c.conversation
+                            .iter()
+                            .map(|p| p.clone().into_db())
+                            .collect::<Vec<_>>()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        let mut users = Vec::new();
+        let mut assets = Vec::new();
+
+        users.push(post.author.clone().into_db());
+        assets.push(post.author.avatar.clone().into_db());
+
+        if let Some(ref list) = post.media {
+            for m in list {
+                medias.push(m.clone().into_db());
+                assets.push(m.clone().get_asset().into_db());
+                if let Some(thumb) = m.clone().get_thumb() {
+                    assets.push(thumb.into_db());
+                }
+            }
+        }
+
+        if let Some(quote) = post.quote.clone() {
+            users.push(quote.author.clone().into_db());
+            assets.push(quote.author.avatar.clone().into_db());
+
+            if let Some(ref list) = quote.media {
+                for m in list {
+                    medias.push(m.clone().into_db());
+                    assets.push(m.clone().get_asset().into_db());
+                    if let Some(thumb) = m.clone().get_thumb() {
+                        assets.push(m.clone().get_asset().into_db());
+                        assets.push(thumb.into_db());
+                    }
+                }
+            }
+        }
+
+        // 回复里所有作者和他们的头像、以及回复中的 media
+        if let Some(ref list) = post.replies {
+            for c in list {
+                // 用户和头像
+                for p in &c.conversation {
+                    users.push(p.0.author.clone().into_db());
+                    assets.push(p.0.author.avatar.clone().into_db());
+                }
+                // 回复里的媒体
+                for p in &c.conversation {
+                    if let Some(ref mlist) = p.0.media {
+                        for m in mlist {
+                            medias.push(m.clone().into_db());
+                            assets.push(m.clone().get_asset().into_db());
+                            if let Some(thumb) = m.clone().get_thumb() {
+                                assets.push(thumb.into_db());
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut tasks = Vec::new();
+
+        // 语音空间卡片交给 SpaceFetch 任务异步补全
+        let space_ids = [
+            post.card.as_ref(),
+            post.quote.as_ref().and_then(|q| q.card.as_ref()),
+        ]
+        .into_iter()
+        .flatten()
+        .filter_map(|c| c.space_id.as_deref());
+        tasks.extend(space_ids.map(DbSpace::fetch_task));
+
+        tasks.extend(
+            assets
+                .iter()
+                .map(|a| a.clone().into_task(task_kind.clone())),
+        );
+
+        DbEntitie {
+            like: Vec::new(),
+            bookmark: Vec::new(),
+            list_posts: Vec::new(),
+            search_posts: Vec::new(),
+            posts,
+            medias,
+            users,
+            assets,
+            replies,
+            tasks,
+        }
+    }
 }
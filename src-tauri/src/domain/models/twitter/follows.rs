@@ -0,0 +1,178 @@
+use crate::database::enums::table::Table;
+use crate::database::{query_raw, query_take, Crud, HasId};
+use crate::utils::serialize::{i64_from_string_or_number, i64_to_string};
+
+use super::users::{DbUser, User};
+
+use crate::{impl_crud, impl_id, impl_schema};
+use anyhow::Result;
+use chrono::Utc;
+use futures::future;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::collections::HashSet;
+use surrealdb::sql::Datetime;
+use surrealdb::RecordId;
+
+/// 关注者或正在关注
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+pub enum FollowKind {
+    Followers,
+    Following,
+}
+
+impl FollowKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FollowKind::Followers => "followers",
+            FollowKind::Following => "following",
+        }
+    }
+}
+
+/// 某个时刻的关注列表快照，成员以数字 id 记录
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct FollowSnapshot {
+    #[serde(serialize_with = "i64_to_string")]
+    #[serde(deserialize_with = "i64_from_string_or_number")]
+    #[specta(type = String)]
+    pub id: i64,
+    #[serde(serialize_with = "i64_to_string")]
+    #[serde(deserialize_with = "i64_from_string_or_number")]
+    #[specta(type = String)]
+    pub user_id: i64,
+    pub kind: FollowKind,
+    pub count: u32,
+    /// 翻到了最后一页，未完成的快照不适合用来对比
+    pub complete: bool,
+    pub taken_at: String,
+}
+
+/// 两次快照之间新增与移除的账号
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct FollowDiff {
+    pub added: Vec<User>,
+    pub removed: Vec<User>,
+    /// 库里找不到资料的账号数量
+    pub missing: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DbFollowSnapshot {
+    pub id: RecordId,
+    pub user_id: i64,
+    pub kind: FollowKind,
+    pub members: Vec<i64>,
+    pub complete: bool,
+    pub taken_at: Datetime,
+}
+
+impl_crud!(DbFollowSnapshot, Table::FollowSnapshot);
+impl_id!(DbFollowSnapshot, id);
+impl_schema!(
+    DbFollowSnapshot,
+    r#"
+    DEFINE INDEX follow_snapshot_owner ON TABLE follow_snapshot FIELDS user_id, kind;
+"#
+);
+
+impl DbFollowSnapshot {
+    /// 以创建时间的毫秒数作 id
+    pub fn new(user_id: i64, kind: FollowKind) -> Self {
+        let now = Utc::now();
+        Self {
+            id: DbFollowSnapshot::record_id(now.timestamp_millis()),
+            user_id,
+            kind,
+            members: Vec::new(),
+            complete: false,
+            taken_at: now.into(),
+        }
+    }
+
+    pub fn into_domain(self) -> Result<FollowSnapshot> {
+        Ok(FollowSnapshot {
+            id: self
+                .id
+                .key()
+                .to_string()
+                .parse::<i64>()
+                .map_err(|e| anyhow::anyhow!("failed to parse snapshot id: {}", e))?,
+            user_id: self.user_id,
+            kind: self.kind,
+            count: self.members.len() as u32,
+            complete: self.complete,
+            taken_at: self.taken_at.to_string(),
+        })
+    }
+
+    /// 追加一页成员，分页偶尔会重复返回同一账号，按集合合并
+    pub async fn append_members(id: RecordId, members: &[i64]) -> Result<()> {
+        if members.is_empty() {
+            return Ok(());
+        }
+        let list = members
+            .iter()
+            .map(|m| m.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let sql = format!("UPDATE {id} SET members = array::union(members, [{list}]) RETURN NONE;");
+        query_raw(&sql).await?.check()?;
+        Ok(())
+    }
+
+    pub async fn select_by_user(user_id: i64, kind: FollowKind) -> Result<Vec<Self>> {
+        let sql = format!(
+            "SELECT * FROM {} WHERE user_id = {user_id} AND kind = '{:?}' ORDER BY taken_at DESC;",
+            Table::FollowSnapshot,
+            kind
+        );
+        query_take(&sql, None).await
+    }
+
+    /// 删除没拍完的快照，它们来自失败、进入死信或被取消的扫描
+    pub async fn delete_incomplete(user_id: i64, kind: FollowKind) -> Result<()> {
+        let sql = format!(
+            "DELETE {} WHERE user_id = {user_id} AND kind = '{:?}' AND complete = false RETURN NONE;",
+            Table::FollowSnapshot,
+            kind
+        );
+        query_raw(&sql).await?.check()?;
+        Ok(())
+    }
+}
+
+/// 对比两次快照，`from` 为较早的一次
+pub async fn diff_snapshots(from: DbFollowSnapshot, to: DbFollowSnapshot) -> Result<FollowDiff> {
+    let before = from.members.iter().copied().collect::<HashSet<_>>();
+    let after = to.members.iter().copied().collect::<HashSet<_>>();
+    let added = to
+        .members
+        .iter()
+        .copied()
+        .filter(|id| !before.contains(id))
+        .collect::<Vec<_>>();
+    let removed = from
+        .members
+        .iter()
+        .copied()
+        .filter(|id| !after.contains(id))
+        .collect::<Vec<_>>();
+
+    let resolve = |ids: Vec<i64>| async move {
+        let users = DbUser::select_by_rest_ids(&ids).await?;
+        let found = users.len();
+        let users = future::join_all(users.into_iter().map(DbUser::into_domain)).await;
+        Ok::<_, anyhow::Error>((
+            users.into_iter().flatten().collect::<Vec<_>>(),
+            ids.len().saturating_sub(found),
+        ))
+    };
+    let ((added, missing_added), (removed, missing_removed)) =
+        tokio::try_join!(resolve(added), resolve(removed))?;
+    Ok(FollowDiff {
+        added,
+        removed,
+        missing: (missing_added + missing_removed) as u32,
+    })
+}
@@ -1,14 +1,13 @@
 use crate::database::enums::table::Table;
-use crate::database::{Crud, HasId, Order, QueryKind};
+use crate::database::{query_raw, Crud, HasId, Order, QueryKind};
 use crate::domain::platform::TaskKind;
 use crate::utils::serialize::into_u32_from_string_or_number;
 
 use super::entities::DbEntitie;
-use super::post::{DbPost, Post};
+use super::post::{load_page, DbPost, Post, PostRecord};
 
-use crate::{impl_crud, impl_id};
+use crate::{impl_crud, impl_id, impl_schema};
 use anyhow::Result;
-use futures::future;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use specta::Type;
@@ -19,12 +18,20 @@ pub struct LikedPost {
     #[serde(deserialize_with = "into_u32_from_string_or_number")]
     pub sortidx: u32,
     pub post: Post,
+    /// 来自哪个账号，多账号之前抓取的记录为空
+    #[serde(default)]
+    pub account: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DbLikedPost {
     pub id: RecordId,
     pub post: RecordId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<i64>,
+    /// 旧记录没有该字段，sortidx 就是记录 id，启动时由 `claim_unattributed` 迁移
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sortidx: Option<u32>,
 }
 
 impl_id!(DbLikedPost, id);
@@ -32,61 +39,34 @@ impl_id!(DbLikedPost, id);
 impl LikedPost {
     pub async fn take(num: i64, end: i64) -> Result<Vec<Self>> {
         let start = (end - num).max(0);
-        let dbresult = DbLikedPost::range_select(start, end).await?;
-        let futures = dbresult.into_iter().map(|record| async move {
-            match record.clone().into_domain().await {
-                Ok(domain) => Some(domain),
-                Err(e) => {
-                    dbg!("Error converting record to domain:", e, record.post);
-                    None
-                }
-            }
-        });
-        let results = future::join_all(futures).await;
-        let result: Vec<Self> = results.into_iter().filter_map(|x| x).collect();
-
-        Ok(result)
+        let sql = format!(
+            "SELECT * FROM {} WHERE sortidx >= {start} AND sortidx <= {end} ORDER BY sortidx;",
+            Table::LikedPost
+        );
+        let dbresult = DbLikedPost::query_take(&sql, None).await?;
+        load_page(dbresult).await
     }
 
     pub async fn select_all() -> Result<Vec<Self>> {
         let dbresult = DbLikedPost::select_all().await?;
-        let futures = dbresult.into_iter().map(|record| async move {
-            match record.clone().into_domain().await {
-                Ok(domain) => Some(domain),
-                Err(e) => {
-                    dbg!("Error converting record to domain:", e, record.post);
-                    None
-                }
-            }
-        });
-        let results = future::join_all(futures).await;
-        let result: Vec<Self> = results.into_iter().filter_map(|x| x).collect();
-
-        Ok(result)
+        load_page(dbresult).await
     }
 
-    pub async fn select_pagin(count: i64, cursor: Option<u32>) -> Result<Vec<Self>> {
-        let cursor = cursor.map(|c| DbLikedPost::record_id(c as i64));
-        let dbresult = DbLikedPost::select_pagin(count, cursor).await?;
-        let futures = dbresult.into_iter().map(|record| async move {
-            match record.clone().into_domain().await {
-                Ok(domain) => Some(domain),
-                Err(e) => {
-                    dbg!("Error converting record to domain:", e, record.post);
-                    None
-                }
-            }
-        });
-        let results = future::join_all(futures).await;
-        let result: Vec<Self> = results.into_iter().filter_map(|x| x).collect();
-
-        Ok(result)
+    pub async fn select_pagin(
+        account: Option<i64>,
+        count: i64,
+        cursor: Option<u32>,
+    ) -> Result<Vec<Self>> {
+        let dbresult = DbLikedPost::select_pagin(account, count, cursor).await?;
+        load_page(dbresult).await
     }
 
-    pub async fn select_single(sortidx: u32) -> Result<Self> {
-        let id = DbLikedPost::record_id(sortidx as i64);
+    pub async fn select_single(account: Option<i64>, sortidx: u32) -> Result<Self> {
+        let id = DbLikedPost::key(account, sortidx);
         let dbresult = DbLikedPost::select_record(id).await?;
-        dbresult.into_domain().await
+        let mut result = dbresult.into_domain().await?;
+        Post::fill_spaces([&mut result.post]).await?;
+        Ok(result)
     }
 
     pub fn from_api(json: &Value) -> Option<Self> {
@@ -94,7 +74,11 @@ impl LikedPost {
             .pointer("/content/itemContent/tweet_results/result")
             .and_then(Post::from_api)?;
         // let sortidx = json.pointer("/sortIndex")?.as_str()?.parse::<i64>().ok()?;
-        Some(Self { sortidx: 0, post })
+        Some(Self {
+            sortidx: 0,
+            post,
+            account: None,
+        })
     }
 
     pub fn into_db(self) -> DbLikedPost {
@@ -103,131 +87,64 @@ impl LikedPost {
 
     pub fn into_entities(self, task_kind: TaskKind) -> DbEntitie {
         let like = self.clone().into_db();
-
-        let mut posts = Vec::new();
-        posts.push(self.clone().post.into_db());
-        if let Some(quote) = self.post.quote.clone() {
-            posts.push(quote.into_db());
-        }
-
-        let mut medias = self
-            .post
-            .media
-            .clone()
-            .map(|list| list.into_iter().map(|m| m.into_db()).collect::<Vec<_>>())
-            .unwrap_or_default();
-
-        let replies = self
-            .post
-            .replies
-            .clone()
-            .map(|list| {
-                list.into_iter()
-                    .flat_map(|c| {
-                        c.conversation
-                            .iter()
-                            .map(|p| p.clone().into_db())
-                            .collect::<Vec<_>>()
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .unwrap_or_default();
-        let mut users = Vec::new();
-        let mut assets = Vec::new();
-
-        users.push(self.post.author.clone().into_db());
-        assets.push(self.post.author.avatar.clone().into_db());
-
-        if let Some(ref list) = self.post.media {
-            for m in list {
-                medias.push(m.clone().into_db());
-                assets.push(m.clone().get_asset().into_db());
-                if let Some(thumb) = m.clone().get_thumb() {
-                    assets.push(thumb.into_db());
-                }
-            }
-        }
-
-        if let Some(quote) = self.post.quote.clone() {
-            users.push(quote.author.clone().into_db());
-            assets.push(quote.author.avatar.clone().into_db());
-
-            if let Some(ref list) = quote.media {
-                for m in list {
-                    medias.push(m.clone().into_db());
-                    assets.push(m.clone().get_asset().into_db());
-                    if let Some(thumb) = m.clone().get_thumb() {
-                        assets.push(m.clone().get_asset().into_db());
-                        assets.push(thumb.into_db());
-                    }
-                }
-            }
-        }
-
-        // 回复里所有作者和他们的头像、以及回复中的 media
-        if let Some(ref list) = self.post.replies {
-            for c in list {
-                // 用户和头像
-                for p in &c.conversation {
-                    users.push(p.0.author.clone().into_db());
-                    assets.push(p.0.author.avatar.clone().into_db());
-                }
-                // 回复里的媒体
-                for p in &c.conversation {
-                    if let Some(ref mlist) = p.0.media {
-                        for m in mlist {
-                            assets.push(m.clone().get_asset().into_db());
-                            if let Some(thumb) = m.clone().get_thumb() {
-                                assets.push(thumb.into_db());
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        let mut tasks = Vec::new();
-
-        tasks.extend(
-            assets
-                .iter()
-                .map(|a| a.clone().into_task(task_kind.clone())),
-        );
-
-        DbEntitie {
-            like: vec![like],
-            posts,
-            medias,
-            users,
-            assets,
-            replies,
-            tasks,
-        }
+        let mut entities = DbEntitie::from_post(self.post, task_kind);
+        entities.like.push(like);
+        entities
     }
 }
 
 impl_crud!(DbLikedPost, Table::LikedPost);
+impl_schema!(
+    DbLikedPost,
+    r#"
+    DEFINE INDEX liked_post_account ON TABLE liked_post FIELDS account;
+    DEFINE INDEX liked_post_sortidx ON TABLE liked_post FIELDS sortidx;
+    DEFINE INDEX liked_post_account_sortidx ON TABLE liked_post FIELDS account, sortidx;
+"#
+);
 
 impl DbLikedPost {
     pub async fn into_domain(self) -> Result<LikedPost> {
         Ok(LikedPost {
-            sortidx: self
+            sortidx: self.sortidx()?,
+            post: DbPost::get(self.post).await?,
+            account: self.account.map(|a| a.to_string()),
+        })
+    }
+
+    pub fn new(post: RecordId, account: Option<i64>, sortidx: u32) -> Self {
+        Self {
+            id: DbLikedPost::key(account, sortidx),
+            post,
+            account,
+            sortidx: Some(sortidx),
+        }
+    }
+
+    /// 各账号的扫描从各自的开始时间倒数 sortidx，主键带上账号才不会互相覆盖
+    pub fn key(account: Option<i64>, sortidx: u32) -> RecordId {
+        let account = account.map(|a| a.to_string()).unwrap_or_default();
+        DbLikedPost::record_id(format!("{account}_{sortidx}"))
+    }
+
+    fn sortidx(&self) -> Result<u32> {
+        match self.sortidx {
+            Some(sortidx) => Ok(sortidx),
+            None => self
                 .id
                 .key()
                 .to_string()
                 .parse::<u32>()
-                .map_err(|e| anyhow::anyhow!("failed to parse rest_id: {}", e))?,
-            post: DbPost::get(self.post).await?,
-        })
+                .map_err(|e| anyhow::anyhow!("failed to parse sortidx: {}", e)),
+        }
     }
 
     pub fn from_domain(domain: LikedPost) -> Self {
-        let sortidx_parsed = domain.sortidx;
-        let post_id = domain.post.rest_id;
-        Self {
-            id: DbLikedPost::record_id(sortidx_parsed as i64),
-            post: DbPost::record_id(post_id),
-        }
+        Self::new(
+            DbPost::record_id(domain.post.rest_id),
+            domain.account.and_then(|a| a.parse::<i64>().ok()),
+            domain.sortidx,
+        )
     }
 
     pub async fn get(id: RecordId) -> Result<LikedPost> {
@@ -235,26 +152,91 @@ impl DbLikedPost {
         data.into_domain().await
     }
 
-    pub async fn select_pagin(count: i64, cursor: Option<RecordId>) -> Result<Vec<Self>> {
-        DbLikedPost::query_take(
-            &QueryKind::pagin(
+    /// 多账号之前的喜欢都来自默认账号，补上归属，并把只有 sortidx 的旧主键换成带账号的主键
+    ///
+    /// 还没有默认账号时只迁移主键，归属留到下次启动。
+    pub async fn claim_unattributed(account: Option<i64>) -> Result<()> {
+        let sql = format!(
+            "SELECT * FROM {} WHERE account = NONE OR sortidx = NONE;",
+            Table::LikedPost
+        );
+        let mut stale = Vec::new();
+        let mut claimed = Vec::new();
+        for record in DbLikedPost::query_take(&sql, None).await? {
+            let next = Self::new(
+                record.post.clone(),
+                record.account.or(account),
+                record.sortidx()?,
+            );
+            // 主键里带着账号，补上归属或迁移旧主键都会换成新的记录
+            if next.id != record.id {
+                stale.push(record.id.to_string());
+                claimed.push(next);
+            }
+        }
+        if claimed.is_empty() {
+            return Ok(());
+        }
+        DbLikedPost::insert_jump(claimed).await?;
+        for chunk in stale.chunks(1_000) {
+            let sql = format!("DELETE [{}] RETURN NONE;", chunk.join(", "));
+            query_raw(&sql).await?.check()?;
+        }
+        Ok(())
+    }
+
+    /// 指定账号时只取该账号的记录，游标为上一页最后一条的 sortidx
+    pub async fn select_pagin(
+        account: Option<i64>,
+        count: i64,
+        cursor: Option<u32>,
+    ) -> Result<Vec<Self>> {
+        let sql = match account {
+            None => QueryKind::pagin(
                 Table::LikedPost,
                 count,
                 cursor.map(|c| c.to_string()),
                 Order::Desc,
-                "id",
+                "sortidx",
             ),
-            None,
-        )
-        .await
-        .map_err(|e| e.into())
+            Some(account) => {
+                let before = cursor
+                    .map(|c| format!(" AND sortidx < {c}"))
+                    .unwrap_or_default();
+                format!(
+                    "SELECT * FROM {} WHERE account = {account}{before} ORDER BY sortidx DESC LIMIT {count};",
+                    Table::LikedPost
+                )
+            }
+        };
+        DbLikedPost::query_take(&sql, None)
+            .await
+            .map_err(|e| e.into())
+    }
+}
+
+#[async_trait::async_trait]
+impl PostRecord for DbLikedPost {
+    type Domain = LikedPost;
+    fn post_id(&self) -> RecordId {
+        self.post.clone()
+    }
+    async fn load(self) -> Result<LikedPost> {
+        self.into_domain().await
+    }
+    fn post_mut(domain: &mut LikedPost) -> &mut Post {
+        &mut domain.post
     }
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn take_single_like(sortidx: u32) -> Result<LikedPost, String> {
-    LikedPost::select_single(sortidx)
+pub async fn take_single_like(sortidx: u32, account: Option<String>) -> Result<LikedPost, String> {
+    let account = account
+        .map(|a| a.parse::<i64>())
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    LikedPost::select_single(account, sortidx)
         .await
         .map_err(|e| e.to_string())
 }
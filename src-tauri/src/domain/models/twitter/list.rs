@@ -0,0 +1,194 @@
+use crate::database::enums::table::Table;
+use crate::database::{query_take, Crud, HasId};
+use crate::domain::platform::TaskKind;
+use crate::utils::serialize::{
+    i64_from_string_or_number, i64_to_string, into_u32_from_string_or_number,
+};
+
+use super::entities::DbEntitie;
+use super::post::{load_page, DbPost, Post, PostRecord};
+use super::users::{DbUser, User};
+
+use crate::{impl_crud, impl_id, impl_schema};
+use anyhow::Result;
+use futures::future;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use surrealdb::sql::Datetime;
+use surrealdb::RecordId;
+
+/// 作为来源订阅的 X List，记录名称与成员
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct ListSource {
+    #[serde(serialize_with = "i64_to_string")]
+    #[serde(deserialize_with = "i64_from_string_or_number")]
+    #[specta(type = String)]
+    pub list_id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub members: Vec<User>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DbListSource {
+    pub id: RecordId,
+    pub name: String,
+    pub description: Option<String>,
+    pub members: Vec<RecordId>,
+    pub updated_at: Datetime,
+}
+
+impl_crud!(DbListSource, Table::ListSource);
+impl_id!(DbListSource, id);
+
+impl DbListSource {
+    pub fn new(
+        list_id: i64,
+        name: String,
+        description: Option<String>,
+        members: Vec<User>,
+    ) -> Self {
+        Self {
+            id: DbListSource::record_id(list_id),
+            name,
+            description,
+            members: members
+                .iter()
+                .map(|u| DbUser::record_id(u.id.as_str()))
+                .collect(),
+            updated_at: chrono::Utc::now().into(),
+        }
+    }
+
+    pub async fn into_domain(self) -> Result<ListSource> {
+        let members = future::join_all(self.members.into_iter().map(DbUser::get)).await;
+        Ok(ListSource {
+            list_id: self
+                .id
+                .key()
+                .to_string()
+                .parse::<i64>()
+                .map_err(|e| anyhow::anyhow!("failed to parse list_id: {}", e))?,
+            name: self.name,
+            description: self.description,
+            // 成员记录可能已被清理，缺失的跳过
+            members: members.into_iter().flatten().collect(),
+            updated_at: Some(self.updated_at.to_string()),
+        })
+    }
+}
+
+/// 列表时间线中的一条推文，排序方式与 LikedPost 相同，按列表分开存放
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct ListedPost {
+    #[serde(serialize_with = "i64_to_string")]
+    #[serde(deserialize_with = "i64_from_string_or_number")]
+    #[specta(type = String)]
+    pub list_id: i64,
+    #[serde(deserialize_with = "into_u32_from_string_or_number")]
+    pub sortidx: u32,
+    pub post: Post,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DbListPost {
+    pub id: RecordId,
+    pub list: RecordId,
+    pub post: RecordId,
+    pub sortidx: u32,
+}
+
+impl_crud!(DbListPost, Table::ListPost);
+impl_id!(DbListPost, id);
+impl_schema!(
+    DbListPost,
+    r#"
+    DEFINE INDEX list_sortidx ON TABLE list_post FIELDS list, sortidx;
+"#
+);
+
+impl ListedPost {
+    pub async fn select_pagin(list_id: i64, count: i64, cursor: Option<u32>) -> Result<Vec<Self>> {
+        let dbresult = DbListPost::select_pagin(list_id, count, cursor).await?;
+        load_page(dbresult).await
+    }
+
+    pub fn into_db(self) -> DbListPost {
+        DbListPost::from_domain(self)
+    }
+
+    pub fn into_entities(self, task_kind: TaskKind) -> DbEntitie {
+        let listed = self.clone().into_db();
+        let mut entities = DbEntitie::from_post(self.post, task_kind);
+        entities.list_posts.push(listed);
+        entities
+    }
+}
+
+impl DbListPost {
+    pub async fn into_domain(self) -> Result<ListedPost> {
+        Ok(ListedPost {
+            list_id: self
+                .list
+                .key()
+                .to_string()
+                .parse::<i64>()
+                .map_err(|e| anyhow::anyhow!("failed to parse list_id: {}", e))?,
+            sortidx: self.sortidx,
+            post: DbPost::get(self.post).await?,
+        })
+    }
+
+    /// 同一条推文在同一列表里只记一次，不同列表互不影响
+    pub fn from_domain(domain: ListedPost) -> Self {
+        Self {
+            id: DbListPost::record_id(format!("{}_{}", domain.list_id, domain.post.rest_id)),
+            list: DbListSource::record_id(domain.list_id),
+            post: DbPost::record_id(domain.post.rest_id),
+            sortidx: domain.sortidx,
+        }
+    }
+
+    pub async fn select_pagin(list_id: i64, count: i64, cursor: Option<u32>) -> Result<Vec<Self>> {
+        let list = DbListSource::record_id(list_id);
+        let sql = match cursor {
+            Some(c) => format!(
+                "SELECT * FROM {} WHERE list = {list} AND sortidx < {c} ORDER BY sortidx DESC LIMIT {count};",
+                Table::ListPost
+            ),
+            None => format!(
+                "SELECT * FROM {} WHERE list = {list} ORDER BY sortidx DESC LIMIT {count};",
+                Table::ListPost
+            ),
+        };
+        query_take(&sql, None).await
+    }
+}
+
+#[async_trait::async_trait]
+impl PostRecord for DbListPost {
+    type Domain = ListedPost;
+    fn post_id(&self) -> RecordId {
+        self.post.clone()
+    }
+    async fn load(self) -> Result<ListedPost> {
+        self.into_domain().await
+    }
+    fn post_mut(domain: &mut ListedPost) -> &mut Post {
+        &mut domain.post
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn all_list_sources() -> Result<Vec<ListSource>, String> {
+    let sources = DbListSource::select_all()
+        .await
+        .map_err(|e| e.to_string())?;
+    let results = future::join_all(sources.into_iter().map(DbListSource::into_domain)).await;
+    results
+        .into_iter()
+        .collect::<Result<Vec<_>>>()
+        .map_err(|e| e.to_string())
+}
@@ -1,8 +1,14 @@
 pub mod asset;
+pub mod bookmark;
 pub mod content_to_copy;
 pub mod entities;
+pub mod follows;
 pub mod like;
+pub mod list;
 pub mod media;
 pub mod post;
+pub mod search;
+pub mod space;
+pub mod upstream;
 pub mod users;
 pub mod utils;
@@ -1,10 +1,11 @@
 use super::{
     media::{DbMedia, Media},
+    space::{AudioSpace, DbSpace},
     users::{DbUser, User},
 };
-use crate::{database::enums::table::Table, domain::models::collect::DbCollection};
-use crate::database::{Crud, HasId};
+use crate::database::{query_take, Crud, HasId};
 use crate::utils::serialize::{i64_from_string_or_number, i64_to_string};
+use crate::{database::enums::table::Table, domain::models::collect::DbCollection};
 use crate::{impl_crud, impl_id};
 use anyhow::{Error, Result};
 use futures::future::join_all;
@@ -86,6 +87,12 @@ pub struct Card {
     pub title: Option<String>,
     pub description: Option<String>,
     pub url: String,
+    /// 语音空间卡片的空间 id，信息由 SpaceFetch 任务异步补全
+    #[serde(default)]
+    pub space_id: Option<String>,
+    /// 读取时从缓存填充
+    #[serde(default)]
+    pub space: Option<AudioSpace>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Type)]
@@ -160,6 +167,7 @@ impl Card {
 
                 // 3. 基础字段：title, description, card_url
                 let mut title = get_binding("title");
+                let mut space_id = None;
                 let mut description = get_binding("description");
                 let card_url = get_binding("card_url")?;
 
@@ -236,22 +244,10 @@ impl Card {
                         }
                     }
                     // 5b. narrow_cast_space_type → AudioSpace
+                    // 解析是同步的，这里只记下空间 id，标题等由 SpaceFetch 任务补全
                     else if get_binding("narrow_cast_space_type").is_some() {
-                        let cast_id = get_binding("id")?;
-                        // 假设你有一个方法可以拿到空间信息
-                        let space_info = Self::fetch_space_info(&cast_id)?;
-                        if let Some(meta) = space_info.pointer("/data/audioSpace/metadata") {
-                            title = meta
-                                .get("title")
-                                .and_then(Value::as_str)
-                                .map(str::to_string);
-                            if let Some(started) = meta.get("started_at").and_then(Value::as_str) {
-                                description = Some(format!("AudioSpace · started at {}", started));
-                            }
-                        } else {
-                            title = Some("unavailable AudioSpace".to_string());
-                            // 这里你可以选择把 url 置空或直接返回 None
-                        }
+                        space_id = Some(get_binding("id")?);
+                        title = Some("AudioSpace".to_string());
                     }
                     // 5c. URL 含 “spaces” 直接跳过
                     else if url.contains("spaces") {
@@ -271,15 +267,34 @@ impl Card {
                     title,
                     description,
                     url,
+                    space_id,
+                    space: None,
                 })
             }
         }
     }
 
-    /// 这个方法只是示例，你需要自己实现实际调用逻辑
-    fn fetch_space_info(_cast_id: &str) -> Option<Value> {
-        // TODO: 调用 API 或查询缓存，返回 serde_json::Value
-        None
+    /// 用缓存的语音空间信息补全卡片标题与描述
+    fn apply_space(&mut self, space: AudioSpace) {
+        if space.available {
+            if let Some(t) = space.title.clone() {
+                self.title = Some(t);
+            }
+            let mut parts = vec!["AudioSpace".to_string()];
+            if let Some(host) = &space.host {
+                parts.push(format!("@{}", host.id));
+            }
+            if let Some(count) = space.participant_count {
+                parts.push(format!("{} participants", count));
+            }
+            if let Some(started) = &space.started_at {
+                parts.push(format!("started at {}", started));
+            }
+            self.description = Some(parts.join(" · "));
+        } else {
+            self.title = Some("unavailable AudioSpace".to_string());
+        }
+        self.space = Some(space);
     }
 }
 
@@ -463,6 +478,38 @@ impl Post {
             .map_err(Error::from)
     }
 
+    /// 为一页帖子（含引用帖）的语音空间卡片补全信息，整页只查一次库
+    ///
+    /// 没有语音空间卡片的页面不会访问数据库；尚未拉取到的空间保持原样。
+    pub async fn fill_spaces<'a>(posts: impl IntoIterator<Item = &'a mut Post>) -> Result<()> {
+        let mut cards = posts
+            .into_iter()
+            .flat_map(|post| {
+                let Post { card, quote, .. } = post;
+                let quote = quote.as_mut().and_then(|q| q.card.as_mut());
+                card.as_mut().into_iter().chain(quote)
+            })
+            .filter(|card| card.space_id.is_some())
+            .collect::<Vec<_>>();
+        if cards.is_empty() {
+            return Ok(());
+        }
+        let mut ids = cards
+            .iter()
+            .filter_map(|card| card.space_id.clone())
+            .collect::<Vec<_>>();
+        ids.sort();
+        ids.dedup();
+        let spaces = DbSpace::get_many(&ids).await?;
+        for card in cards.iter_mut() {
+            let space = card.space_id.as_ref().and_then(|id| spaces.get(id));
+            if let Some(space) = space.cloned() {
+                card.apply_space(space);
+            }
+        }
+        Ok(())
+    }
+
     pub fn to_quote(&self) -> QuotePost {
         QuotePost {
             rest_id: self.rest_id.clone(),
@@ -601,6 +648,33 @@ impl_crud!(DbReply, Table::Reply);
 impl_id!(DbPost, id);
 impl_id!(DbReply, 0.id);
 
+/// 喜欢、书签、列表与搜索里指向帖子的记录，分页读取时共用 `load_page`
+#[async_trait::async_trait]
+pub trait PostRecord: Sized + Send + 'static {
+    type Domain: Send;
+    fn post_id(&self) -> RecordId;
+    async fn load(self) -> Result<Self::Domain>;
+    fn post_mut(domain: &mut Self::Domain) -> &mut Post;
+}
+
+/// 并发转换一页记录，转换失败的跳过并记录日志，最后为整页补全语音空间信息
+pub async fn load_page<R: PostRecord>(records: Vec<R>) -> Result<Vec<R::Domain>> {
+    let results = join_all(records.into_iter().map(|record| async move {
+        let post = record.post_id();
+        record.load().await.map_err(|e| (post, e))
+    }))
+    .await;
+    let mut page = Vec::with_capacity(results.len());
+    for result in results {
+        match result {
+            Ok(item) => page.push(item),
+            Err((post, e)) => tracing::warn!("记录转换失败，跳过 {}: {}", post, e),
+        }
+    }
+    Post::fill_spaces(page.iter_mut().map(R::post_mut)).await?;
+    Ok(page)
+}
+
 impl DbPost {
     async fn convert_media(media_ids: Vec<RecordId>) -> Result<Vec<Media>> {
         let media_results = join_all(
@@ -740,4 +814,29 @@ impl DbPost {
         let data: DbPost = DbPost::select_record(id).await?;
         data.into_domain().await
     }
+
+    /// 某个作者最新的若干条帖子，rest_id 越大越新
+    pub async fn select_ids_by_author(author: RecordId, count: i64) -> Result<Vec<RecordId>> {
+        let sql = format!(
+            "SELECT VALUE id FROM {} WHERE author = {author} ORDER BY id DESC LIMIT {count};",
+            Table::Post
+        );
+        query_take(&sql, None).await
+    }
+
+    /// 按 rest_id 倒序分批取出主帖 id，`before` 为上一批最后一条
+    pub async fn select_root_ids_before(before: Option<i64>, count: i64) -> Result<Vec<RecordId>> {
+        let sql = match before {
+            Some(b) => format!(
+                "SELECT VALUE id FROM {} WHERE is_root = true AND id < {} ORDER BY id DESC LIMIT {count};",
+                Table::Post,
+                DbPost::record_id(b)
+            ),
+            None => format!(
+                "SELECT VALUE id FROM {} WHERE is_root = true ORDER BY id DESC LIMIT {count};",
+                Table::Post
+            ),
+        };
+        query_take(&sql, None).await
+    }
 }
@@ -0,0 +1,217 @@
+use crate::database::enums::table::Table;
+use crate::database::{query_raw, query_take, Crud, HasId};
+use crate::domain::platform::TaskKind;
+use crate::utils::serialize::{
+    i64_from_string_or_number, i64_to_string, into_u32_from_string_or_number,
+};
+
+use super::entities::DbEntitie;
+use super::post::{load_page, DbPost, Post, PostRecord};
+
+use crate::{impl_crud, impl_id, impl_schema};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use surrealdb::RecordId;
+
+/// SearchTimeline 的结果分类，对应网页搜索页的标签
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+pub enum SearchProduct {
+    Top,
+    Latest,
+    Media,
+}
+
+impl SearchProduct {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SearchProduct::Top => "Top",
+            SearchProduct::Latest => "Latest",
+            SearchProduct::Media => "Media",
+        }
+    }
+}
+
+/// 保存的搜索，例如 `from:someone filter:media`
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct SavedSearch {
+    #[serde(serialize_with = "i64_to_string")]
+    #[serde(deserialize_with = "i64_from_string_or_number")]
+    #[specta(type = String)]
+    pub id: i64,
+    pub query: String,
+    pub product: SearchProduct,
+    /// 定时运行的间隔，为空时只手动运行
+    pub interval_mins: Option<u32>,
+    pub last_run_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DbSavedSearch {
+    pub id: RecordId,
+    pub query: String,
+    pub product: SearchProduct,
+    pub interval_mins: Option<u32>,
+    pub last_run_at: Option<DateTime<Utc>>,
+}
+
+impl_crud!(DbSavedSearch, Table::SavedSearch);
+impl_id!(DbSavedSearch, id);
+
+impl DbSavedSearch {
+    /// 以创建时间的毫秒数作 id
+    pub fn new(query: String, product: SearchProduct, interval_mins: Option<u32>) -> Self {
+        Self {
+            id: DbSavedSearch::record_id(Utc::now().timestamp_millis()),
+            query,
+            product,
+            interval_mins,
+            last_run_at: None,
+        }
+    }
+
+    pub fn into_domain(self) -> Result<SavedSearch> {
+        Ok(SavedSearch {
+            id: self
+                .id
+                .key()
+                .to_string()
+                .parse::<i64>()
+                .map_err(|e| anyhow::anyhow!("failed to parse search id: {}", e))?,
+            query: self.query,
+            product: self.product,
+            interval_mins: self.interval_mins,
+            last_run_at: self.last_run_at.map(|t| t.to_rfc3339()),
+        })
+    }
+
+    /// 到了定时运行的时间
+    pub fn is_due(&self, now: DateTime<Utc>) -> bool {
+        match (self.interval_mins, self.last_run_at) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(mins), Some(last)) => now - last >= chrono::Duration::minutes(mins as i64),
+        }
+    }
+}
+
+/// 搜索命中的一条推文，排序方式与 LikedPost 相同，按保存的搜索分开存放
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct SearchedPost {
+    #[serde(serialize_with = "i64_to_string")]
+    #[serde(deserialize_with = "i64_from_string_or_number")]
+    #[specta(type = String)]
+    pub search_id: i64,
+    #[serde(deserialize_with = "into_u32_from_string_or_number")]
+    pub sortidx: u32,
+    pub post: Post,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DbSearchPost {
+    pub id: RecordId,
+    pub search: RecordId,
+    pub post: RecordId,
+    pub sortidx: u32,
+}
+
+impl_crud!(DbSearchPost, Table::SearchPost);
+impl_id!(DbSearchPost, id);
+impl_schema!(
+    DbSearchPost,
+    r#"
+    DEFINE INDEX search_sortidx ON TABLE search_post FIELDS search, sortidx;
+"#
+);
+
+impl SearchedPost {
+    pub async fn select_pagin(
+        search_id: i64,
+        count: i64,
+        cursor: Option<u32>,
+    ) -> Result<Vec<Self>> {
+        let dbresult = DbSearchPost::select_pagin(search_id, count, cursor).await?;
+        load_page(dbresult).await
+    }
+
+    pub fn into_db(self) -> DbSearchPost {
+        DbSearchPost::from_domain(self)
+    }
+
+    pub fn into_entities(self, task_kind: TaskKind) -> DbEntitie {
+        let searched = self.clone().into_db();
+        let mut entities = DbEntitie::from_post(self.post, task_kind);
+        entities.search_posts.push(searched);
+        entities
+    }
+}
+
+impl DbSearchPost {
+    pub async fn into_domain(self) -> Result<SearchedPost> {
+        Ok(SearchedPost {
+            search_id: self
+                .search
+                .key()
+                .to_string()
+                .parse::<i64>()
+                .map_err(|e| anyhow::anyhow!("failed to parse search id: {}", e))?,
+            sortidx: self.sortidx,
+            post: DbPost::get(self.post).await?,
+        })
+    }
+
+    /// 同一条推文在同一个搜索里只记一次，这也是重跑时的 seen 集合
+    pub fn from_domain(domain: SearchedPost) -> Self {
+        Self {
+            id: DbSearchPost::record_id(format!("{}_{}", domain.search_id, domain.post.rest_id)),
+            search: DbSavedSearch::record_id(domain.search_id),
+            post: DbPost::record_id(domain.post.rest_id),
+            sortidx: domain.sortidx,
+        }
+    }
+
+    pub async fn select_pagin(
+        search_id: i64,
+        count: i64,
+        cursor: Option<u32>,
+    ) -> Result<Vec<Self>> {
+        let search = DbSavedSearch::record_id(search_id);
+        let sql = match cursor {
+            Some(c) => format!(
+                "SELECT * FROM {} WHERE search = {search} AND sortidx < {c} ORDER BY sortidx DESC LIMIT {count};",
+                Table::SearchPost
+            ),
+            None => format!(
+                "SELECT * FROM {} WHERE search = {search} ORDER BY sortidx DESC LIMIT {count};",
+                Table::SearchPost
+            ),
+        };
+        query_take(&sql, None).await
+    }
+
+    /// 删除保存的搜索时一并清理它的命中记录，帖子本身保留
+    pub async fn delete_by_search(search_id: i64) -> Result<()> {
+        let sql = format!(
+            "DELETE {} WHERE search = {} RETURN NONE;",
+            Table::SearchPost,
+            DbSavedSearch::record_id(search_id)
+        );
+        query_raw(&sql).await?.check()?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl PostRecord for DbSearchPost {
+    type Domain = SearchedPost;
+    fn post_id(&self) -> RecordId {
+        self.post.clone()
+    }
+    async fn load(self) -> Result<SearchedPost> {
+        self.into_domain().await
+    }
+    fn post_mut(domain: &mut SearchedPost) -> &mut Post {
+        &mut domain.post
+    }
+}
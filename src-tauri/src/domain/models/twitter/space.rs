@@ -0,0 +1,176 @@
+use crate::database::enums::table::Table;
+use std::collections::HashMap;
+
+use crate::database::{query_take, Crud, HasId};
+use crate::domain::platform::{scheduler, Task, TaskKind};
+
+use super::users::{DbUser, User};
+
+use crate::{impl_crud, impl_id};
+use anyhow::Result;
+use chrono::DateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use surrealdb::sql::Datetime;
+use surrealdb::RecordId;
+
+/// 卡片引用的语音空间，时间均为 RFC 3339
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct AudioSpace {
+    pub id: String,
+    pub title: Option<String>,
+    pub host: Option<User>,
+    /// Running / Ended / NotStarted 等
+    pub state: Option<String>,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub participant_count: Option<u32>,
+    /// 接口查不到，可能已被删除
+    pub available: bool,
+}
+
+/// 语音空间信息的缓存，以空间 id 作 key
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DbSpace {
+    pub id: RecordId,
+    pub title: Option<String>,
+    pub host: Option<RecordId>,
+    pub state: Option<String>,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub participant_count: Option<u32>,
+    pub available: bool,
+    pub fetched_at: Datetime,
+}
+
+impl_crud!(DbSpace, Table::Space);
+impl_id!(DbSpace, id);
+
+/// 接口里的毫秒时间戳有时是数字有时是字符串
+fn millis_to_rfc3339(value: Option<&Value>) -> Option<String> {
+    let ms = match value? {
+        Value::Number(n) => n.as_i64()?,
+        Value::String(s) => s.parse::<i64>().ok()?,
+        _ => return None,
+    };
+    DateTime::from_timestamp_millis(ms).map(|t| t.to_rfc3339())
+}
+
+impl DbSpace {
+    /// 解析 AudioSpaceById 的响应，同时返回主持人以便入库
+    pub fn from_api(space_id: &str, json: &Value) -> (Self, Option<User>) {
+        let id = DbSpace::record_id(space_id);
+        let Some(meta) = json.pointer("/data/audioSpace/metadata") else {
+            return (
+                Self {
+                    id,
+                    title: None,
+                    host: None,
+                    state: None,
+                    started_at: None,
+                    ended_at: None,
+                    participant_count: None,
+                    available: false,
+                    fetched_at: chrono::Utc::now().into(),
+                },
+                None,
+            );
+        };
+        let host = meta
+            .pointer("/creator_results/result/legacy")
+            .and_then(User::from_api);
+        let participant_count = json
+            .pointer("/data/audioSpace/participants/total")
+            .or_else(|| meta.get("total_live_listeners"))
+            .and_then(Value::as_u64)
+            .map(|n| n as u32);
+        let space = Self {
+            id,
+            title: meta
+                .get("title")
+                .and_then(Value::as_str)
+                .map(|s| s.to_string()),
+            host: host.as_ref().map(|u| DbUser::record_id(u.id.as_str())),
+            state: meta
+                .get("state")
+                .and_then(Value::as_str)
+                .map(|s| s.to_string()),
+            started_at: millis_to_rfc3339(meta.get("started_at")),
+            ended_at: millis_to_rfc3339(meta.get("ended_at")),
+            participant_count,
+            available: true,
+            fetched_at: chrono::Utc::now().into(),
+        };
+        (space, host)
+    }
+
+    /// 已结束或查不到的空间不会再变化，无需再次请求
+    pub fn is_final(&self) -> bool {
+        !self.available || self.state.as_deref() == Some("Ended")
+    }
+
+    pub async fn into_domain(self) -> Result<AudioSpace> {
+        let host = match self.host {
+            Some(host) => DbUser::get(host).await.ok(),
+            None => None,
+        };
+        Ok(AudioSpace {
+            id: self.id.key().to_string(),
+            title: self.title,
+            host,
+            state: self.state,
+            started_at: self.started_at,
+            ended_at: self.ended_at,
+            participant_count: self.participant_count,
+            available: self.available,
+        })
+    }
+
+    pub async fn get(space_id: &str) -> Result<AudioSpace> {
+        DbSpace::select_record(DbSpace::record_id(space_id))
+            .await?
+            .into_domain()
+            .await
+    }
+
+    /// 一次查出一页卡片引用的空间，按空间 id 返回，查不到的不在结果中
+    pub async fn get_many(space_ids: &[String]) -> Result<HashMap<String, AudioSpace>> {
+        if space_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let targets = space_ids
+            .iter()
+            .map(|id| DbSpace::record_id(id.as_str()).to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let spaces: Vec<DbSpace> = query_take(&format!("SELECT * FROM [{targets}];"), None).await?;
+        let mut result = HashMap::with_capacity(spaces.len());
+        for space in spaces {
+            let space = space.into_domain().await?;
+            result.insert(space.id.clone(), space);
+        }
+        Ok(result)
+    }
+
+    /// 拉取空间信息的任务，与帖子同一批入库，由调度器异步执行
+    pub fn fetch_task(space_id: &str) -> Task {
+        let kind = TaskKind::SpaceFetch;
+        let retry_policy = kind.retry_policy();
+        Task {
+            id: Task::record_id(format!("space:{}", space_id)),
+            tar: DbSpace::record_id(space_id),
+            priority: kind.priority(),
+            kind,
+            payload: None,
+            status: scheduler::Status::Pending,
+            result: None,
+            error: None,
+            retry_count: 0,
+            retry_policy,
+            next_attempt_at: None,
+            started_at: None,
+            finished_at: None,
+        }
+    }
+}
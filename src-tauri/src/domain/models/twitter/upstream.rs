@@ -0,0 +1,215 @@
+use crate::database::enums::table::Table;
+use crate::database::{query_take, Crud, HasId};
+
+use super::post::{DbPost, Post};
+
+use crate::{impl_crud, impl_id, impl_schema};
+use anyhow::Result;
+use futures::future;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use surrealdb::sql::Datetime;
+use surrealdb::RecordId;
+
+/// 归档的推文在 X 上现在的状态
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+pub enum UpstreamStatus {
+    Available,
+    /// 已被删除，或因其他原因只剩墓碑
+    Tombstone,
+    Protected,
+    Suspended,
+}
+
+impl UpstreamStatus {
+    /// 根据 TweetResultByRestId 的 `tweetResult`，附带墓碑文字或不可用原因
+    pub fn classify(tweet_result: &Value) -> (Self, Option<String>) {
+        let Some(result) = tweet_result.get("result") else {
+            // 完全查不到，通常是已被删除
+            return (UpstreamStatus::Tombstone, None);
+        };
+        match result.get("__typename").and_then(Value::as_str) {
+            Some("TweetTombstone") => {
+                let text = result
+                    .pointer("/tombstone/text/text")
+                    .and_then(Value::as_str)
+                    .map(|s| s.to_string());
+                let lower = text.clone().unwrap_or_default().to_lowercase();
+                let status = if lower.contains("suspended") {
+                    UpstreamStatus::Suspended
+                } else if lower.contains("limits who can view") || lower.contains("protected") {
+                    UpstreamStatus::Protected
+                } else {
+                    UpstreamStatus::Tombstone
+                };
+                (status, text)
+            }
+            Some("TweetUnavailable") => {
+                let reason = result
+                    .get("reason")
+                    .and_then(Value::as_str)
+                    .map(|s| s.to_string());
+                let status = match reason.as_deref() {
+                    Some("Suspended") => UpstreamStatus::Suspended,
+                    Some("Protected") => UpstreamStatus::Protected,
+                    _ => UpstreamStatus::Tombstone,
+                };
+                (status, reason)
+            }
+            _ => (UpstreamStatus::Available, None),
+        }
+    }
+}
+
+/// 一条帖子最近一次回查的结果
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct PostCheck {
+    pub status: UpstreamStatus,
+    pub reason: Option<String>,
+    pub checked_at: String,
+}
+
+/// 回查结果与库中保存的帖子
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct CheckedPost {
+    pub check: PostCheck,
+    pub post: Post,
+}
+
+/// 与帖子同 key，每次回查覆盖上一次
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DbPostCheck {
+    pub id: RecordId,
+    pub post: RecordId,
+    pub status: UpstreamStatus,
+    pub reason: Option<String>,
+    pub checked_at: Datetime,
+}
+
+impl_crud!(DbPostCheck, Table::PostCheck);
+impl_id!(DbPostCheck, id);
+impl_schema!(
+    DbPostCheck,
+    r#"
+    DEFINE INDEX post_check_status ON TABLE post_check FIELDS status;
+"#
+);
+
+impl DbPostCheck {
+    pub fn new(rest_id: i64, status: UpstreamStatus, reason: Option<String>) -> Self {
+        Self {
+            id: DbPostCheck::record_id(rest_id),
+            post: DbPost::record_id(rest_id),
+            status,
+            reason,
+            checked_at: chrono::Utc::now().into(),
+        }
+    }
+
+    pub fn into_domain(self) -> PostCheck {
+        PostCheck {
+            status: self.status,
+            reason: self.reason,
+            checked_at: self.checked_at.to_string(),
+        }
+    }
+
+    /// 按状态筛选，按帖子 id 倒序分页，`before` 为上一页最后一条的 rest_id
+    pub async fn select_by_status(
+        statuses: &[UpstreamStatus],
+        count: i64,
+        before: Option<i64>,
+    ) -> Result<Vec<Self>> {
+        let list = statuses
+            .iter()
+            .map(|s| format!("'{:?}'", s))
+            .collect::<Vec<_>>()
+            .join(",");
+        let sql = match before {
+            Some(b) => format!(
+                "SELECT * FROM {} WHERE status IN [{list}] AND id < {} ORDER BY id DESC LIMIT {count};",
+                Table::PostCheck,
+                DbPostCheck::record_id(b)
+            ),
+            None => format!(
+                "SELECT * FROM {} WHERE status IN [{list}] ORDER BY id DESC LIMIT {count};",
+                Table::PostCheck
+            ),
+        };
+        query_take(&sql, None).await
+    }
+}
+
+impl CheckedPost {
+    pub async fn select_by_status(
+        statuses: &[UpstreamStatus],
+        count: i64,
+        before: Option<i64>,
+    ) -> Result<Vec<Self>> {
+        let checks = DbPostCheck::select_by_status(statuses, count, before).await?;
+        let results = future::join_all(checks.into_iter().map(|check| async move {
+            let post = DbPost::get(check.post.clone()).await?;
+            Ok::<_, anyhow::Error>(CheckedPost {
+                check: check.into_domain(),
+                post,
+            })
+        }))
+        .await;
+        // 帖子本身被清理掉的跳过
+        let mut result: Vec<Self> = results.into_iter().flatten().collect();
+        Post::fill_spaces(result.iter_mut().map(|c| &mut c.post)).await?;
+        Ok(result)
+    }
+}
+
+/// 按回查状态筛选归档的帖子，比如只看上游已删除、这里仍保留的
+#[tauri::command]
+#[specta::specta]
+pub async fn filter_checked_posts(
+    statuses: Vec<UpstreamStatus>,
+    cursor: Option<String>,
+) -> Result<Vec<CheckedPost>, String> {
+    let before = cursor
+        .map(|c| c.parse::<i64>())
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    CheckedPost::select_by_status(&statuses, 100, before)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_classify() {
+        let available = json!({ "result": { "__typename": "Tweet", "rest_id": "1" } });
+        assert_eq!(
+            UpstreamStatus::classify(&available).0,
+            UpstreamStatus::Available
+        );
+
+        assert_eq!(
+            UpstreamStatus::classify(&json!({})).0,
+            UpstreamStatus::Tombstone
+        );
+
+        let suspended = json!({ "result": {
+            "__typename": "TweetTombstone",
+            "tombstone": { "text": { "text": "This Post is from a suspended account. Learn more" } }
+        }});
+        assert_eq!(
+            UpstreamStatus::classify(&suspended).0,
+            UpstreamStatus::Suspended
+        );
+
+        let protected =
+            json!({ "result": { "__typename": "TweetUnavailable", "reason": "Protected" } });
+        let (status, reason) = UpstreamStatus::classify(&protected);
+        assert_eq!(status, UpstreamStatus::Protected);
+        assert_eq!(reason.as_deref(), Some("Protected"));
+    }
+}
@@ -1,10 +1,10 @@
 use super::asset::{Asset, AssetType, DbAsset};
 use crate::database::enums::table::Table;
-use crate::database::{Crud, HasId};
+use crate::database::{query_raw, query_take, Crud, HasId};
 use crate::domain::models::meta::GlobalVal;
 use crate::domain::models::twitter::asset::FullAssetPath;
 use crate::enums::platform::Platform;
-use crate::{impl_crud, impl_id};
+use crate::{impl_crud, impl_id, impl_schema};
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use specta::Type;
@@ -50,6 +50,13 @@ impl User {
     pub fn into_db(self) -> DbUser {
         DbUser::from_domain(self)
     }
+
+    /// 解析 `user_results.result`，同时取出数字 id
+    pub fn from_result(json: &serde_json::Value) -> Option<(i64, Self)> {
+        let rest_id = json.pointer("/rest_id")?.as_str()?.parse::<i64>().ok()?;
+        let user = json.pointer("/legacy").and_then(User::from_api)?;
+        Some((rest_id, user))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -57,10 +64,19 @@ pub struct DbUser {
     pub id: RecordId,
     pub name: String,
     pub avatar: RecordId,
+    /// 数字 id，只在能拿到时补充；为空时不序列化，避免覆盖写入时把已有的值清掉
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rest_id: Option<i64>,
 }
 
 impl_crud!(DbUser, Table::User);
 impl_id!(DbUser, id);
+impl_schema!(
+    DbUser,
+    r#"
+    DEFINE INDEX user_rest_id ON TABLE user FIELDS rest_id;
+"#
+);
 
 impl DbUser {
     pub async fn into_domain(self) -> Result<User> {
@@ -76,7 +92,34 @@ impl DbUser {
             id: DbUser::record_id(user.id.as_str()),
             name: user.name,
             avatar: DbAsset::from_domain(user.avatar).id,
+            rest_id: None,
+        }
+    }
+
+    /// 为已入库的用户补上数字 id，screen_name 会变，数字 id 不会
+    pub async fn enrich_rest_ids(pairs: &[(i64, RecordId)]) -> Result<()> {
+        if pairs.is_empty() {
+            return Ok(());
+        }
+        let sql = pairs
+            .iter()
+            .map(|(rest_id, id)| format!("UPDATE {id} SET rest_id = {rest_id} RETURN NONE;"))
+            .collect::<String>();
+        query_raw(&sql).await?.check()?;
+        Ok(())
+    }
+
+    pub async fn select_by_rest_ids(rest_ids: &[i64]) -> Result<Vec<Self>> {
+        if rest_ids.is_empty() {
+            return Ok(Vec::new());
         }
+        let ids = rest_ids
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let sql = format!("SELECT * FROM {} WHERE rest_id IN [{ids}];", Table::User);
+        query_take(&sql, None).await
     }
 
     pub async fn get(id: RecordId) -> Result<User> {
@@ -1,8 +1,14 @@
 use super::{
     asset::DbAsset,
+    bookmark::DbBookmark,
+    follows::DbFollowSnapshot,
     like::DbLikedPost,
+    list::{DbListPost, DbListSource},
     media::DbMedia,
     post::{DbPost, DbReply},
+    search::DbSearchPost,
+    space::DbSpace,
+    upstream::DbPostCheck,
     users::DbUser,
 };
 use crate::{database::Crud, domain::platform::job::Job, domain::platform::task::Task};
@@ -10,6 +16,13 @@ use crate::{database::Crud, domain::platform::job::Job, domain::platform::task::
 pub async fn clean_database() -> anyhow::Result<()> {
     DbUser::clean().await?;
     DbLikedPost::clean().await?;
+    DbBookmark::clean().await?;
+    DbListPost::clean().await?;
+    DbListSource::clean().await?;
+    DbSearchPost::clean().await?;
+    DbFollowSnapshot::clean().await?;
+    DbPostCheck::clean().await?;
+    DbSpace::clean().await?;
     DbPost::clean().await?;
     DbReply::clean().await?;
     DbMedia::clean().await?;
@@ -8,19 +8,32 @@ use surrealdb::RecordId;
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Type)]
 pub enum UserKey {
+    /// 默认账号的 cookie
     Twitter,
+    /// 其余 X 账号的 cookie，以账号的数字 id 区分
+    TwitterAccount(
+        #[serde(serialize_with = "i64_to_string")]
+        #[serde(deserialize_with = "i64_from_string_or_number")]
+        #[specta(type = String)]
+        i64,
+    ),
 }
 
 impl UserKey {
-    pub fn as_str(self) -> &'static str {
+    pub fn as_key(&self) -> String {
         match self {
-            UserKey::Twitter => "twitter",
+            UserKey::Twitter => "twitter".to_string(),
+            UserKey::TwitterAccount(id) => format!("twitter_{}", id),
         }
     }
     pub fn from_str(s: &str) -> Result<Self, String> {
         match s {
             "twitter" => Ok(UserKey::Twitter),
-            _ => Err(format!("Unknown UserKey: {}", s)),
+            _ => s
+                .strip_prefix("twitter_")
+                .and_then(|id| id.parse::<i64>().ok())
+                .map(UserKey::TwitterAccount)
+                .ok_or_else(|| format!("Unknown UserKey: {}", s)),
         }
     }
 }
@@ -73,7 +86,7 @@ impl Crud for DbUserKV {
 
 impl DbUserKV {
     pub fn new<T: Into<UserKVValue>>(key: UserKey, value: T) -> Self {
-        let id = DbUserKV::record_id(key.as_str());
+        let id = DbUserKV::record_id(key.as_key());
         Self {
             id,
             value: value.into(),
@@ -81,7 +94,7 @@ impl DbUserKV {
     }
 
     pub async fn get(key: UserKey) -> Result<Option<UserKVValue>, String> {
-        match DbUserKV::select(key.as_str()).await {
+        match DbUserKV::select(key.as_key()).await {
             Ok(data) => Ok(Some(data.value)),
             Err(e) => {
                 if e.to_string().contains("not found") {
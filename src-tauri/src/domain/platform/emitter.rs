@@ -101,9 +101,9 @@ where
 
 /// 后端批量发射函数，将多个 AssetDownloadEvent 打包并 emit
 fn emit_asset_batch(items: Vec<AssetDownloadEvent>) {
-    let app = Scheduler::<Task>::get().unwrap().app.clone();
-    let batch = AssetDownloadBatchEvent { items };
-    let _ = batch.emit(&app);
+    if let Ok(sched) = Scheduler::<Task>::get() {
+        let _ = sched.emit(AssetDownloadBatchEvent { items });
+    }
 }
 
 /// 单例去抖动缓冲器，用于 AssetDownloadEvent
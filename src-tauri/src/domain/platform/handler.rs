@@ -1,5 +1,4 @@
 use std::path::PathBuf;
-use std::sync::LazyLock;
 use std::time::Duration;
 
 use crate::database::Crud;
@@ -7,21 +6,32 @@ use crate::domain::models::meta::GlobalVal;
 use crate::domain::models::twitter::asset::FullAssetPath;
 use crate::domain::models::twitter::entities::DbEntitie;
 use crate::domain::models::twitter::{
-    asset::DbAsset, like::DbLikedPost, media::DbMedia, post::DbPost, post::DbReply, users::DbUser,
+    asset::DbAsset, bookmark::DbBookmark, like::DbLikedPost, list::DbListPost, media::DbMedia,
+    post::DbPost, post::DbReply, search::DbSearchPost, users::DbUser,
 };
 use crate::domain::platform::task::Task;
 use anyhow::anyhow;
 use anyhow::Context;
 use anyhow::Result;
 use futures_util::StreamExt;
-use reqwest::Client;
+use reqwest::header::RANGE;
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
 use specta::Type;
+use surrealdb::RecordId;
 use tauri_specta::Event;
 use tokio::fs;
-use tokio::{fs::File, io::AsyncWriteExt, time::timeout};
+use tokio::{
+    fs::{File, OpenOptions},
+    io::AsyncWriteExt,
+    time::timeout,
+};
+use tokio_util::sync::CancellationToken;
 
 use super::emitter::Emitter;
+use super::proxy;
+use super::retry::{self, TaskError};
+use super::stats;
 use super::Schedulable;
 use super::Status;
 
@@ -30,14 +40,10 @@ pub struct AssetDownloadEvent {
     pub aid: String,
     pub available: bool,
 }
-static HTTP_CLIENT: LazyLock<Client> = LazyLock::new(|| {
-    Client::builder()
-        .connect_timeout(Duration::from_secs(5))
-        .timeout(Duration::from_secs(30))
-        .build()
-        .expect("构造 HTTP Client 失败")
-});
-pub async fn download_asset(task: Task) -> Result<Option<super::HandleSignal>> {
+pub async fn download_asset(
+    task: Task,
+    cancel: CancellationToken,
+) -> Result<Option<super::HandleSignal>> {
     task.update_status(Status::Running, None).await.ok();
 
     let mut asset = DbAsset::select_record(task.tar.clone())
@@ -61,11 +67,24 @@ pub async fn download_asset(task: Task) -> Result<Option<super::HandleSignal>> {
         fs::create_dir_all(parent).await.ok();
     }
 
-    let resp = HTTP_CLIENT
-        .get(&asset.url)
-        .send()
-        .await
-        .context("HTTP 请求 send() 失败")?;
+    // 上次中断留下的 tmp 用 Range 续传，服务端不认时从头下载
+    let mut offset = fs::metadata(&tmp_path).await.map(|m| m.len()).unwrap_or(0);
+    let resp = loop {
+        let mut req = proxy::download_client().get(&asset.url);
+        if offset > 0 {
+            req = req.header(RANGE, format!("bytes={}-", offset));
+        }
+        let resp = tokio::select! {
+            _ = cancel.cancelled() => return Err(TaskError::Cancelled.into()),
+            resp = req.send() => resp.context("HTTP 请求 send() 失败")?,
+        };
+        if offset > 0 && resp.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            fs::remove_file(&tmp_path).await.ok();
+            offset = 0;
+            continue;
+        }
+        break resp;
+    };
 
     let status = resp.status().as_u16();
     if matches!(status, 404 | 403 | 307 | 401) {
@@ -78,43 +97,61 @@ pub async fn download_asset(task: Task) -> Result<Option<super::HandleSignal>> {
         return Ok(None);
     }
     if !resp.status().is_success() {
-        return Err(anyhow!("下载失败，状态码: {}", status));
+        return Err(TaskError::Http {
+            status,
+            url: asset.url.clone(),
+        }
+        .into());
     }
 
-    let mut file = File::create(&tmp_path).await.context("打开 tmp 文件失败")?;
+    let mut file = if offset > 0 && resp.status() == StatusCode::PARTIAL_CONTENT {
+        OpenOptions::new()
+            .append(true)
+            .open(&tmp_path)
+            .await
+            .context("打开 tmp 文件失败")?
+    } else {
+        File::create(&tmp_path).await.context("打开 tmp 文件失败")?
+    };
     let mut stream = resp.bytes_stream();
 
     let mut chunk_count = 0;
     let task_result: anyhow::Result<()> = async {
-        while let Some(chunk) = timeout(Duration::from_secs(10), stream.next())
-            .await
-            .context("分块下载整体超时")?
-        {
+        loop {
+            let next = tokio::select! {
+                _ = cancel.cancelled() => return Err(TaskError::Cancelled.into()),
+                next = timeout(Duration::from_secs(10), stream.next()) => {
+                    next.context("分块下载整体超时")?
+                }
+            };
+            let Some(chunk) = next else {
+                break;
+            };
             let data = chunk.context("读取 chunk 失败")?;
             chunk_count += 1;
             file.write_all(&data).await.context("写入 chunk 失败")?;
+            stats::add_downloaded_bytes(data.len() as u64);
         }
         file.flush().await.context("文件 flush 失败")?;
         Ok(())
     }
     .await;
 
-    match task_result {
-        Ok(()) => {
-            tokio::fs::rename(&tmp_path, &save_path)
-                .await
-                .context("重命名 tmp 文件失败")?;
-            asset.downloaded = true;
-            asset.available = true;
-        }
-        Err(_err) => {
-            // 不标 downloaded, 让调度器自动重试
-            asset.available = false;
-            // 延迟几秒再重试，免得瞬时打爆 CDN
-            tokio::time::sleep(Duration::from_secs(5)).await;
+    if let Err(err) = task_result {
+        // 不标 downloaded，交给调度器重试或记为取消；重试时从 tmp 续传
+        drop(file);
+        if retry::is_cancelled(&err) {
+            fs::remove_file(&tmp_path).await.ok();
         }
+        return Err(err);
     }
 
+    tokio::fs::rename(&tmp_path, &save_path)
+        .await
+        .context("重命名 tmp 文件失败")?;
+    asset.downloaded = true;
+    asset.available = true;
+
     finish_asset_download(&asset).await?;
     println!(
         "下载完成: {:?}, chunk_count: {}",
@@ -135,7 +172,24 @@ async fn finish_asset_download(asset: &DbAsset) -> Result<()> {
     Ok(())
 }
 
-pub async fn transport_asset(task: Task) -> Result<Option<super::HandleSignal>> {
+/// 人工放弃下载时，把资源标记为不可用
+///
+/// 文件并未下载，downloaded 保持原样，恢复与对账流程不会把它当作已存在。
+pub async fn mark_asset_unavailable(id: RecordId) -> Result<()> {
+    let mut asset = DbAsset::select_record(id)
+        .await
+        .context("查询 DbAsset 失败")?;
+    asset.available = false;
+    finish_asset_download(&asset).await
+}
+
+pub async fn transport_asset(
+    task: Task,
+    cancel: CancellationToken,
+) -> Result<Option<super::HandleSignal>> {
+    if cancel.is_cancelled() {
+        return Err(TaskError::Cancelled.into());
+    }
     task.update_status(Status::Running, None).await.ok();
     let mut asset = DbAsset::select_record(task.tar.clone())
         .await
@@ -189,6 +243,9 @@ pub async fn transport_asset(task: Task) -> Result<Option<super::HandleSignal>>
 pub async fn handle_entities(data: DbEntitie) -> Result<Vec<Task>> {
     let DbEntitie {
         like,
+        bookmark,
+        list_posts,
+        search_posts,
         posts,
         medias,
         users,
@@ -198,6 +255,9 @@ pub async fn handle_entities(data: DbEntitie) -> Result<Vec<Task>> {
     } = data;
 
     let f_like = DbLikedPost::insert_jump(like);
+    let f_bookmark = DbBookmark::insert_jump(bookmark);
+    let f_list_posts = DbListPost::insert_jump(list_posts);
+    let f_search_posts = DbSearchPost::insert_jump(search_posts);
     let f_posts = DbPost::insert_jump(posts);
     let f_media = DbMedia::insert_jump(medias);
     let f_users = DbUser::insert_jump(users);
@@ -205,14 +265,38 @@ pub async fn handle_entities(data: DbEntitie) -> Result<Vec<Task>> {
     let f_repls = DbReply::insert_jump(replies);
     let f_tasks = Task::insert_jump(tasks);
 
-    let (_r_like, _r_posts, _r_media, _r_users, _r_assets, _r_repls, _r_tasks) =
-        tokio::join!(f_like, f_posts, f_media, f_users, f_assets, f_repls, f_tasks,);
+    let (
+        _r_like,
+        _r_bookmark,
+        _r_list_posts,
+        _r_search_posts,
+        _r_posts,
+        _r_media,
+        _r_users,
+        _r_assets,
+        _r_repls,
+        _r_tasks,
+    ) = tokio::join!(
+        f_like,
+        f_bookmark,
+        f_list_posts,
+        f_search_posts,
+        f_posts,
+        f_media,
+        f_users,
+        f_assets,
+        f_repls,
+        f_tasks,
+    );
     _r_tasks
 }
 
 pub async fn handle_entities_replace(data: DbEntitie) -> Result<Vec<Task>> {
     let DbEntitie {
         like,
+        bookmark,
+        list_posts,
+        search_posts,
         posts,
         medias,
         users,
@@ -222,6 +306,9 @@ pub async fn handle_entities_replace(data: DbEntitie) -> Result<Vec<Task>> {
     } = data;
 
     let f_like = DbLikedPost::insert_replace(like);
+    let f_bookmark = DbBookmark::insert_replace(bookmark);
+    let f_list_posts = DbListPost::insert_replace(list_posts);
+    let f_search_posts = DbSearchPost::insert_replace(search_posts);
     let f_posts = DbPost::insert_replace(posts);
     let f_media = DbMedia::insert_replace(medias);
     let f_users = DbUser::insert_replace(users);
@@ -229,7 +316,119 @@ pub async fn handle_entities_replace(data: DbEntitie) -> Result<Vec<Task>> {
     let f_repls = DbReply::insert_replace(replies);
     let f_tasks = Task::insert_replace(tasks);
 
-    let (_r_like, _r_posts, _r_media, _r_users, _r_assets, _r_repls, _r_tasks) =
-        tokio::join!(f_like, f_posts, f_media, f_users, f_assets, f_repls, f_tasks,);
+    let (
+        _r_like,
+        _r_bookmark,
+        _r_list_posts,
+        _r_search_posts,
+        _r_posts,
+        _r_media,
+        _r_users,
+        _r_assets,
+        _r_repls,
+        _r_tasks,
+    ) = tokio::join!(
+        f_like,
+        f_bookmark,
+        f_list_posts,
+        f_search_posts,
+        f_posts,
+        f_media,
+        f_users,
+        f_assets,
+        f_repls,
+        f_tasks,
+    );
     _r_tasks
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::twitter::asset::{AssetType, RelAssetPath};
+    use crate::domain::models::twitter::post::DbPost;
+    use crate::domain::platform::sink::AppEvent;
+    use crate::domain::platform::{testing, TaskKind};
+    use crate::enums::platform::Platform;
+
+    #[test]
+    fn test_handle_entities_and_download_existing() {
+        testing::run(async {
+            let asset = DbAsset {
+                id: DbAsset::record_id("media-handler-test.jpg"),
+                ty: AssetType::Media,
+                plat: Platform::Twitter,
+                url: "http://127.0.0.1:9/handler-test.jpg".to_string(),
+                name: "handler-test.jpg".to_string(),
+                path: RelAssetPath(PathBuf::from("media/handler-test.jpg")),
+                downloaded: false,
+                available: false,
+            };
+            let mut entities = DbEntitie::default();
+            entities.assets.push(asset.clone());
+            entities
+                .tasks
+                .push(asset.clone().into_task(TaskKind::AssetDownload));
+
+            let tasks = handle_entities(entities).await.unwrap();
+            assert_eq!(tasks.len(), 1);
+            assert_eq!(tasks[0].tar, asset.id);
+            assert!(Task::select_record(tasks[0].id.clone()).await.is_ok());
+
+            // 文件已在磁盘上时不发请求，直接补标并通知前端
+            let full = asset.path.to_full();
+            std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+            std::fs::write(&full, b"jpg").unwrap();
+            download_asset(tasks[0].clone(), CancellationToken::new())
+                .await
+                .unwrap();
+            let stored = DbAsset::select_record(asset.id.clone()).await.unwrap();
+            assert!(stored.downloaded && stored.available);
+
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            let events = testing::sink().take();
+            assert!(events.iter().any(|e| matches!(
+                e,
+                AppEvent::AssetDownloadBatch(batch)
+                    if batch.items.iter().any(|i| i.aid == asset.name && i.available)
+            )));
+        });
+    }
+
+    #[test]
+    fn test_handle_entities_keeps_overlapping_accounts() {
+        testing::run(async {
+            // 两个账号先后开始扫描，sortidx 区间有重叠
+            let mut entities = DbEntitie::default();
+            for (account, start) in [(1001, 5_000), (1002, 5_002)] {
+                for i in 0..5u32 {
+                    let post = DbPost::record_id(account * 100 + i as i64);
+                    let sortidx = start - i;
+                    entities
+                        .like
+                        .push(DbLikedPost::new(post.clone(), Some(account), sortidx));
+                    entities
+                        .bookmark
+                        .push(DbBookmark::new(post, Some(account), sortidx));
+                }
+            }
+            handle_entities(entities).await.unwrap();
+
+            for account in [1001, 1002] {
+                let likes = DbLikedPost::select_pagin(Some(account), 10, None)
+                    .await
+                    .unwrap();
+                assert_eq!(likes.len(), 5);
+                let bookmarks = DbBookmark::select_pagin(Some(account), 10, None)
+                    .await
+                    .unwrap();
+                assert_eq!(bookmarks.len(), 5);
+            }
+            let page = DbLikedPost::select_pagin(Some(1002), 2, Some(5_001))
+                .await
+                .unwrap();
+            assert_eq!(page.len(), 2);
+            assert!(page.iter().all(|l| l.sortidx < Some(5_001)));
+        });
+    }
+}
@@ -5,25 +5,89 @@ use serde_json::{json, Value};
 use specta::Type;
 use surrealdb::RecordId;
 use tauri_specta::Event;
+use tokio_util::sync::CancellationToken;
 
 use crate::database::enums::table::Table;
 use crate::database::{Crud, HasId};
 use crate::domain::platform::Scheduler;
 use crate::{impl_crud, impl_id};
 
+use super::api::bookmark::process_bookmarks_chunk;
+use super::api::follows::process_follows_chunk;
+use super::api::list::process_list_chunk;
+use super::api::recheck::process_recheck_chunk;
+use super::api::search::process_search_chunk;
+use super::api::timeline::job_account;
 use super::api::user::process_likes_chunk;
+use super::api::user_timeline::process_user_timeline_chunk;
+use super::pause::PauseScope;
+use super::retry::RetryPolicy;
 use super::Status;
-use super::{HandleSignal, Schedulable};
+use super::{HandleSignal, Lane, Schedulable};
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, specta::Type)]
 pub enum Mission {
     ScanLikes,
+    ScanBookmarks,
+    ScanUserTimeline,
+    ScanList,
+    ScanSearch,
+    SnapshotFollows,
+    RecheckPosts,
 }
 
 impl Mission {
     pub fn as_str(self) -> &'static str {
         match self {
             Mission::ScanLikes => "scan_likes",
+            Mission::ScanBookmarks => "scan_bookmarks",
+            Mission::ScanUserTimeline => "scan_user_timeline",
+            Mission::ScanList => "scan_list",
+            Mission::ScanSearch => "scan_search",
+            Mission::SnapshotFollows => "snapshot_follows",
+            Mission::RecheckPosts => "recheck_posts",
+        }
+    }
+
+    /// 同一 mission 的并发上限，分页扫描必须串行
+    pub fn concurrency(self) -> usize {
+        match self {
+            Mission::ScanLikes
+            | Mission::ScanBookmarks
+            | Mission::ScanUserTimeline
+            | Mission::ScanList
+            | Mission::ScanSearch
+            | Mission::SnapshotFollows
+            | Mission::RecheckPosts => 1,
+        }
+    }
+
+    pub fn priority(self) -> u8 {
+        match self {
+            Mission::ScanLikes
+            | Mission::ScanBookmarks
+            | Mission::ScanUserTimeline
+            | Mission::ScanList
+            | Mission::ScanSearch
+            | Mission::SnapshotFollows
+            | Mission::RecheckPosts => 1,
+        }
+    }
+
+    pub fn retry_policy(self) -> RetryPolicy {
+        match self {
+            Mission::ScanLikes
+            | Mission::ScanBookmarks
+            | Mission::ScanUserTimeline
+            | Mission::ScanList
+            | Mission::ScanSearch
+            | Mission::SnapshotFollows
+            | Mission::RecheckPosts => RetryPolicy {
+                max_attempts: 3,
+                base_delay_ms: 5_000,
+                max_delay_ms: 600_000,
+                jitter: 0.2,
+            },
         }
     }
 }
@@ -38,6 +102,10 @@ pub struct Job {
     pub error: Option<String>,
     pub retry_count: u32,
     pub max_retry_count: u32,
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
+    #[serde(default)]
+    pub next_attempt_at: Option<DateTime<Utc>>,
     pub started_at: Option<DateTime<Utc>>,
     pub finished_at: Option<DateTime<Utc>>,
 }
@@ -72,6 +140,38 @@ impl Schedulable for Job {
     fn retry_count(&self) -> u32 {
         self.retry_count
     }
+    fn priority(&self) -> u8 {
+        self.mission.priority()
+    }
+    fn lane(&self) -> Lane {
+        Lane {
+            name: self.mission.as_str(),
+            limit: self.mission.concurrency(),
+        }
+    }
+    fn max_concurrency() -> usize {
+        4
+    }
+    fn pause_scope() -> PauseScope {
+        PauseScope::Jobs
+    }
+    /// 尝试次数以 max_retry_count 为准，退避参数取自持久化的策略
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: self.max_retry_count,
+            ..self.retry_policy
+        }
+    }
+    fn next_attempt_at(&self) -> Option<DateTime<Utc>> {
+        self.next_attempt_at
+    }
+    fn set_retry(&mut self, retry_count: u32, next_attempt_at: Option<DateTime<Utc>>) {
+        self.retry_count = retry_count;
+        self.next_attempt_at = next_attempt_at;
+    }
+    fn account(&self) -> Option<i64> {
+        job_account(&self.params)
+    }
     async fn delete(self) -> Result<()> {
         Job::delete_record(self.id).await?;
         Ok(())
@@ -87,9 +187,8 @@ impl Schedulable for Job {
             true => {
                 if !self.end_band.is_empty() && self.mission == Mission::ScanLikes {
                     print!("emit need_refresh");
-                    let app = Scheduler::<Job>::get()?.app.clone();
-                    ScanLikesIncEvent { need_refresh: true }
-                        .emit(&app)
+                    Scheduler::<Job>::get()?
+                        .emit(ScanLikesIncEvent { need_refresh: true })
                         .map_err(|e| anyhow!("emit ScanLikesIncEvent 失败: {}", e))?;
                 }
 
@@ -111,12 +210,22 @@ impl Schedulable for Job {
         Job::merge(self.id.clone(), data).await?;
         Ok(())
     }
-    async fn handle(self) -> Result<Option<HandleSignal>> {
+    async fn handle(self, cancel: CancellationToken) -> Result<Option<HandleSignal>> {
         match self.mission {
-            Mission::ScanLikes => process_likes_chunk(self).await,
+            Mission::ScanLikes => process_likes_chunk(self, cancel).await,
+            Mission::ScanBookmarks => process_bookmarks_chunk(self, cancel).await,
+            Mission::ScanUserTimeline => process_user_timeline_chunk(self, cancel).await,
+            Mission::ScanList => process_list_chunk(self, cancel).await,
+            Mission::ScanSearch => process_search_chunk(self, cancel).await,
+            Mission::SnapshotFollows => process_follows_chunk(self, cancel).await,
+            Mission::RecheckPosts => process_recheck_chunk(self, cancel).await,
         }
     }
     async fn load_pending() -> Result<Vec<Self>> {
-        Job::get_jobs().await
+        let jobs = Job::get_jobs().await?;
+        Ok(jobs
+            .into_iter()
+            .filter(|j| !matches!(j.status, Status::DeadLetter | Status::Cancelled))
+            .collect())
     }
 }
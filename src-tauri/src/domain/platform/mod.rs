@@ -1,8 +1,17 @@
 pub mod emitter;
 pub mod handler;
 pub mod job;
+pub mod pause;
+pub mod proxy;
+pub mod queue;
+pub mod recovery;
+pub mod retry;
 pub mod scheduler;
+pub mod sink;
+pub mod stats;
 pub mod task;
+#[cfg(test)]
+pub mod testing;
 pub mod twitter;
 
 pub use handler::*;
@@ -0,0 +1,102 @@
+use std::collections::HashSet;
+use std::sync::{LazyLock, RwLock};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::database::enums::meta::MetaKey;
+use crate::database::Crud;
+use crate::domain::models::meta::DbMeta;
+
+use super::job::Job;
+use super::scheduler::Scheduler;
+use super::{Task, TaskKind};
+
+/// 暂停的作用范围
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Type)]
+pub enum PauseScope {
+    /// 整个 `Scheduler<Task>`
+    Tasks,
+    /// 整个 `Scheduler<Job>`
+    Jobs,
+    /// 某一类 Task，其余类型照常执行
+    Kind(TaskKind),
+    /// 某个账号的凭证失效，只搁置用到该账号的 Task 与 Job，值为账号 id
+    Account(String),
+}
+
+static PAUSED: LazyLock<RwLock<HashSet<PauseScope>>> =
+    LazyLock::new(|| RwLock::new(HashSet::new()));
+
+/// 从 meta 中恢复上次的暂停状态，需在调度器启动前调用
+pub async fn init() -> Result<()> {
+    let Some(v) = DbMeta::get(MetaKey::PausedScopes).await? else {
+        return Ok(());
+    };
+    let scopes: Vec<PauseScope> = serde_json::from_str(&v.into_string()).unwrap_or_default();
+    *PAUSED.write().unwrap() = scopes.into_iter().collect();
+    Ok(())
+}
+
+pub fn is_paused(scope: &PauseScope) -> bool {
+    PAUSED.read().unwrap().contains(scope)
+}
+
+/// lane 名与 `TaskKind::as_str` 一致，暂停某类 Task 即暂停对应 lane
+pub fn is_lane_paused(lane: &str) -> bool {
+    PAUSED.read().unwrap().iter().any(|scope| match scope {
+        PauseScope::Kind(kind) => kind.as_str() == lane,
+        _ => false,
+    })
+}
+
+pub fn is_account_paused(account: i64) -> bool {
+    PAUSED
+        .read()
+        .unwrap()
+        .contains(&PauseScope::Account(account.to_string()))
+}
+
+pub fn paused_scopes() -> Vec<PauseScope> {
+    PAUSED.read().unwrap().iter().cloned().collect()
+}
+
+/// 修改暂停状态并持久化，返回状态是否真的发生了变化
+pub async fn set_paused(scope: PauseScope, paused: bool) -> Result<bool> {
+    let account = match &scope {
+        PauseScope::Account(id) => id.parse::<i64>().ok(),
+        _ => None,
+    };
+    let changed = {
+        let mut guard = PAUSED.write().unwrap();
+        if paused {
+            guard.insert(scope)
+        } else {
+            guard.remove(&scope)
+        }
+    };
+    if !changed {
+        return Ok(false);
+    }
+    let data = serde_json::to_string(&paused_scopes())?;
+    DbMeta::new(MetaKey::PausedScopes.as_str().to_string(), data)
+        .upsert()
+        .await?;
+    if !paused {
+        // worker 可能正因 lane 被暂停而等待，恢复后需要唤醒；恢复账号时放回搁置的任务
+        if let Ok(sched) = Scheduler::<Task>::get() {
+            if let Some(account) = account {
+                sched.unpark(account);
+            }
+            sched.wake();
+        }
+        if let Ok(sched) = Scheduler::<Job>::get() {
+            if let Some(account) = account {
+                sched.unpark(account);
+            }
+            sched.wake();
+        }
+    }
+    Ok(true)
+}
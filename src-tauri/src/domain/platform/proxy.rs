@@ -0,0 +1,174 @@
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use reqwest::{Client, ClientBuilder, NoProxy, Proxy};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use url::Url;
+
+use crate::database::enums::meta::MetaKey;
+use crate::database::Crud;
+use crate::domain::models::meta::DbMeta;
+
+/// 出站代理设置，接口请求与资源下载共用
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Type)]
+pub struct ProxySettings {
+    pub enabled: bool,
+    /// `http://`、`https://`、`socks5://` 或 `socks5h://`（由代理解析域名）
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    /// 不走代理的主机，支持 `*.example.com`、IP 与 CIDR
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+}
+
+impl ProxySettings {
+    fn to_proxy(&self) -> Result<Option<Proxy>> {
+        if !self.enabled || self.url.trim().is_empty() {
+            return Ok(None);
+        }
+        let mut url = Url::parse(self.url.trim())?;
+        let is_socks = match url.scheme() {
+            "http" | "https" => false,
+            "socks5" | "socks5h" => true,
+            other => return Err(anyhow!("不支持的代理协议: {}", other)),
+        };
+        let mut proxy = match (&self.username, is_socks) {
+            // SOCKS5 的认证只能写在 URL 里
+            (Some(user), true) => {
+                url.set_username(user)
+                    .map_err(|_| anyhow!("无法设置代理用户名"))?;
+                url.set_password(self.password.as_deref())
+                    .map_err(|_| anyhow!("无法设置代理密码"))?;
+                Proxy::all(url.as_str())?
+            }
+            (Some(user), false) => {
+                Proxy::all(url.as_str())?.basic_auth(user, self.password.as_deref().unwrap_or(""))
+            }
+            (None, _) => Proxy::all(url.as_str())?,
+        };
+        if !self.no_proxy.is_empty() {
+            proxy = proxy.no_proxy(NoProxy::from_string(&self.no_proxy.join(",")));
+        }
+        Ok(Some(proxy))
+    }
+}
+
+/// 按当前代理设置构造的 client，设置变更时整体替换
+struct Clients {
+    settings: ProxySettings,
+    api: Client,
+    download: Client,
+}
+
+fn build_clients(settings: ProxySettings) -> Result<Clients> {
+    let proxy = settings.to_proxy()?;
+    let with_proxy = |builder: ClientBuilder| match proxy.clone() {
+        Some(p) => builder.proxy(p),
+        None => builder,
+    };
+    let api = with_proxy(
+        Client::builder()
+            .connect_timeout(Duration::from_secs(10))
+            .timeout(Duration::from_secs(60)),
+    )
+    .build()?;
+    let download = with_proxy(
+        Client::builder()
+            .connect_timeout(Duration::from_secs(5))
+            .timeout(Duration::from_secs(30)),
+    )
+    .build()?;
+    Ok(Clients {
+        settings,
+        api,
+        download,
+    })
+}
+
+static CLIENTS: LazyLock<RwLock<Clients>> = LazyLock::new(|| {
+    RwLock::new(build_clients(ProxySettings::default()).expect("构造 HTTP Client 失败"))
+});
+
+/// 请求 X 接口用的 client
+pub fn api_client() -> Client {
+    CLIENTS.read().unwrap().api.clone()
+}
+
+/// 下载媒体资源用的 client
+pub fn download_client() -> Client {
+    CLIENTS.read().unwrap().download.clone()
+}
+
+fn apply(settings: ProxySettings) -> Result<()> {
+    let clients = build_clients(settings)?;
+    *CLIENTS.write().unwrap() = clients;
+    Ok(())
+}
+
+/// 从 meta 中恢复代理设置，需在调度器启动前调用
+pub async fn init() -> Result<()> {
+    let Some(v) = DbMeta::get(MetaKey::ProxySettings).await? else {
+        return Ok(());
+    };
+    let settings: ProxySettings = serde_json::from_str(&v.into_string())?;
+    apply(settings)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_proxy_settings() -> Result<ProxySettings, String> {
+    Ok(CLIENTS.read().unwrap().settings.clone())
+}
+
+/// 保存并立即生效，之后发出的请求都会走新的代理
+#[tauri::command]
+#[specta::specta]
+pub async fn set_proxy_settings(settings: ProxySettings) -> Result<(), String> {
+    // 先构造一次，设置有误时不落库
+    let clients = build_clients(settings.clone()).map_err(|e| e.to_string())?;
+    let data = serde_json::to_string(&settings).map_err(|e| e.to_string())?;
+    DbMeta::new(MetaKey::ProxySettings.as_str().to_string(), data)
+        .upsert()
+        .await
+        .map_err(|e| e.to_string())?;
+    *CLIENTS.write().unwrap() = clients;
+    tracing::info!("代理设置已更新，enabled={}", settings.enabled);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(url: &str) -> ProxySettings {
+        ProxySettings {
+            enabled: true,
+            url: url.to_string(),
+            username: Some("user".to_string()),
+            password: Some("pass".to_string()),
+            no_proxy: vec!["localhost".to_string(), "*.internal".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_to_proxy() {
+        assert!(settings("http://proxy.corp:8080")
+            .to_proxy()
+            .unwrap()
+            .is_some());
+        assert!(settings("socks5h://127.0.0.1:1080")
+            .to_proxy()
+            .unwrap()
+            .is_some());
+        assert!(settings("ftp://proxy.corp").to_proxy().is_err());
+
+        let disabled = ProxySettings {
+            enabled: false,
+            ..settings("http://proxy.corp:8080")
+        };
+        assert!(disabled.to_proxy().unwrap().is_none());
+    }
+}
@@ -0,0 +1,129 @@
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+
+use surrealdb::RecordId;
+use tokio::sync::OwnedSemaphorePermit;
+
+use super::scheduler::{Lane, Schedulable};
+
+/// 队列中的条目，按 priority 降序、入队顺序升序排列
+struct Queued<T> {
+    priority: u8,
+    seq: u64,
+    id: RecordId,
+    item: T,
+}
+
+impl<T> PartialEq for Queued<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority == other.priority && self.seq == other.seq
+    }
+}
+
+impl<T> Eq for Queued<T> {}
+
+impl<T> PartialOrd for Queued<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Ord for Queued<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+/// 按 lane 分组的优先级队列，每个 lane 内部是一个大顶堆
+///
+/// 同一 id 只保留一个有效条目：重复 push 会以更高优先级重新入堆，
+/// 旧条目留在堆里，出堆时按 `live` 中记录的 seq 识别并丢弃。
+pub struct PriorityQueue<T: Schedulable> {
+    lanes: HashMap<&'static str, BinaryHeap<Queued<T>>>,
+    live: HashMap<RecordId, (u8, u64)>,
+    seq: u64,
+}
+
+impl<T: Schedulable> PriorityQueue<T> {
+    pub fn new() -> Self {
+        Self {
+            lanes: HashMap::new(),
+            live: HashMap::new(),
+            seq: 0,
+        }
+    }
+
+    /// 入队；id 已在队列中时不重复排队，而是提升其优先级
+    pub fn push(&mut self, item: T) {
+        self.seq += 1;
+        let id = item.id();
+        let priority = match self.live.get(&id) {
+            Some((cur, _)) => (*cur).max(item.priority()).saturating_add(1),
+            None => item.priority(),
+        };
+        self.live.insert(id.clone(), (priority, self.seq));
+        let lane = item.lane().name;
+        self.lanes.entry(lane).or_default().push(Queued {
+            priority,
+            seq: self.seq,
+            id,
+            item,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.live.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.live.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.lanes.clear();
+        self.live.clear();
+    }
+
+    fn is_stale(live: &HashMap<RecordId, (u8, u64)>, q: &Queued<T>) -> bool {
+        live.get(&q.id).map(|(_, seq)| *seq) != Some(q.seq)
+    }
+
+    /// 丢弃各 lane 堆顶已被覆盖的旧条目
+    fn purge_stale(&mut self) {
+        for heap in self.lanes.values_mut() {
+            while heap.peek().is_some_and(|q| Self::is_stale(&self.live, q)) {
+                heap.pop();
+            }
+        }
+    }
+
+    /// 取出优先级最高、且其 lane 还有空闲并发的条目
+    ///
+    /// lane 已满的条目留在队列里，不会阻塞其他 lane。
+    pub fn pop_ready<F>(&mut self, mut acquire: F) -> Option<(T, OwnedSemaphorePermit)>
+    where
+        F: FnMut(Lane) -> Option<OwnedSemaphorePermit>,
+    {
+        self.purge_stale();
+        let mut heads: Vec<(&'static str, u8, u64, Lane)> = self
+            .lanes
+            .iter()
+            .filter_map(|(name, heap)| {
+                heap.peek()
+                    .map(|q| (*name, q.priority, q.seq, q.item.lane()))
+            })
+            .collect();
+        heads.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.2.cmp(&b.2)));
+
+        for (name, _, _, lane) in heads {
+            if let Some(permit) = acquire(lane) {
+                let queued = self.lanes.get_mut(name)?.pop()?;
+                self.live.remove(&queued.id);
+                return Some((queued.item, permit));
+            }
+        }
+        None
+    }
+}
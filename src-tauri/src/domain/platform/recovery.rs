@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use surrealdb::RecordId;
+use tokio::fs;
+
+use crate::database::enums::table::Table;
+use crate::database::{query_raw, query_take, Crud};
+use crate::domain::models::meta::GlobalVal;
+use crate::domain::models::twitter::asset::{DbAsset, RelAssetPath};
+
+use super::scheduler::Status;
+use super::{Task, TaskKind};
+
+/// 启动时的恢复流程，必须在调度器 init 之前执行
+///
+/// 上次异常退出时留下的 Running 状态、`.tmp` 文件和与磁盘不一致的
+/// `DbAsset.downloaded` 都在这里统一修正，之后 `load_pending` 拿到的就是干净的数据。
+pub async fn recover() -> Result<()> {
+    reset_running().await?;
+    let Some(save_dir) = GlobalVal::get_save_dir() else {
+        return Ok(());
+    };
+    reconcile_assets().await?;
+    clean_orphan_tmp(save_dir).await?;
+    Ok(())
+}
+
+/// 把残留的 Running 改回 Pending，调度器会重新派发
+async fn reset_running() -> Result<()> {
+    for table in [Table::Task, Table::Status] {
+        let sql = format!(
+            "UPDATE {table} SET status = '{:?}' WHERE status = '{:?}' RETURN NONE;",
+            Status::Pending,
+            Status::Running,
+        );
+        query_raw(&sql).await?.check()?;
+    }
+    Ok(())
+}
+
+/// 以磁盘为准修正 downloaded 标记
+///
+/// 文件已存在但未标记的直接补标；标记为已下载但文件丢失的，重置并补建下载任务。
+/// 检查文件是否存在放到阻塞线程池里做，修正按批写回。
+async fn reconcile_assets() -> Result<()> {
+    let assets = DbAsset::select_all().await?;
+    let paths = assets.iter().map(|a| a.path.to_full()).collect::<Vec<_>>();
+    let exists =
+        tokio::task::spawn_blocking(move || paths.iter().map(|p| p.exists()).collect::<Vec<_>>())
+            .await?;
+
+    let mut found = Vec::new();
+    let mut missing = Vec::new();
+    for (asset, exists) in assets.into_iter().zip(exists) {
+        if exists && !(asset.downloaded && asset.available) {
+            found.push(asset.id);
+        } else if !exists && asset.downloaded && asset.available {
+            missing.push(asset);
+        }
+    }
+    set_downloaded(&found, true).await?;
+    set_downloaded(
+        &missing.iter().map(|a| a.id.clone()).collect::<Vec<_>>(),
+        false,
+    )
+    .await?;
+    if !missing.is_empty() {
+        tracing::warn!("{} 个已下载资源在磁盘上丢失，重新加入下载", missing.len());
+        let tasks = missing
+            .into_iter()
+            .map(|a| a.into_task(TaskKind::AssetDownload))
+            .collect();
+        Task::insert_jump(tasks).await?;
+    }
+    Ok(())
+}
+
+/// 批量修改 downloaded；标记为已下载的同时视为可用
+async fn set_downloaded(ids: &[RecordId], downloaded: bool) -> Result<()> {
+    let set = match downloaded {
+        true => "downloaded = true, available = true",
+        false => "downloaded = false",
+    };
+    for chunk in ids.chunks(1_000) {
+        let targets = chunk
+            .iter()
+            .map(|id| id.to_string())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let sql = format!("UPDATE [{targets}] SET {set} RETURN NONE;");
+        query_raw(&sql).await?.check()?;
+    }
+    Ok(())
+}
+
+/// 删除没有对应下载任务的 `.tmp`；仍有任务的保留，下载时按 Range 续传
+async fn clean_orphan_tmp(save_dir: PathBuf) -> Result<()> {
+    let sql = format!(
+        "RETURN (SELECT tar.path AS path FROM {} WHERE kind = '{:?}' AND status IN ['{:?}', '{:?}', '{:?}']).path;",
+        Table::Task,
+        TaskKind::AssetDownload,
+        Status::Pending,
+        Status::Running,
+        Status::Failed,
+    );
+    let paths: Vec<RelAssetPath> = query_take(&sql, None).await?;
+    let keep: HashSet<PathBuf> = paths
+        .into_iter()
+        .map(|p| p.to_full().with_extension("tmp"))
+        .collect();
+
+    let mut dirs = vec![save_dir];
+    while let Some(dir) = dirs.pop() {
+        let Ok(mut entries) = fs::read_dir(&dir).await else {
+            continue;
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if entry.file_type().await?.is_dir() {
+                dirs.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "tmp") && !keep.contains(&path) {
+                fs::remove_file(&path).await.ok();
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,188 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use thiserror::Error;
+
+/// 可被调度器识别的错误，用于区分是否值得重试
+#[derive(Debug, Error)]
+pub enum TaskError {
+    #[error("HTTP {status}: {url}")]
+    Http { status: u16, url: String },
+    #[error("parse error: {0}")]
+    Parse(String),
+    #[error("rate limited until {reset_at}")]
+    RateLimited { reset_at: DateTime<Utc> },
+    #[error("cancelled")]
+    Cancelled,
+    /// 凭证失效（过期、锁定或封禁），重试没有意义；`account` 为失效凭证所属的账号
+    #[error("unauthorized ({status}): {reason}")]
+    Unauthorized {
+        status: u16,
+        reason: String,
+        account: Option<i64>,
+    },
+}
+
+impl TaskError {
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            TaskError::Http { status, .. } => {
+                matches!(*status, 408 | 429) || (500..600).contains(status)
+            }
+            TaskError::Parse(_) => false,
+            TaskError::RateLimited { .. } => true,
+            TaskError::Cancelled => false,
+            TaskError::Unauthorized { .. } => false,
+        }
+    }
+}
+
+/// 被限流时返回额度恢复时间，这类失败不计入重试次数
+pub fn rate_limited_until(err: &anyhow::Error) -> Option<DateTime<Utc>> {
+    err.chain()
+        .find_map(|cause| match cause.downcast_ref::<TaskError>() {
+            Some(TaskError::RateLimited { reset_at }) => Some(*reset_at),
+            _ => None,
+        })
+}
+
+pub fn is_cancelled(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        matches!(
+            cause.downcast_ref::<TaskError>(),
+            Some(TaskError::Cancelled)
+        )
+    })
+}
+
+/// 凭证失效时返回 Some，内层是失效凭证所属的账号（cookie 中没有 twid 时为空）
+pub fn unauthorized_account(err: &anyhow::Error) -> Option<Option<i64>> {
+    err.chain()
+        .find_map(|cause| match cause.downcast_ref::<TaskError>() {
+            Some(TaskError::Unauthorized { account, .. }) => Some(*account),
+            _ => None,
+        })
+}
+
+/// 判断错误是否可重试；无法识别的错误（网络抖动、超时等）默认可重试
+pub fn is_retryable(err: &anyhow::Error) -> bool {
+    for cause in err.chain() {
+        if let Some(e) = cause.downcast_ref::<TaskError>() {
+            return e.is_retryable();
+        }
+        if cause.downcast_ref::<serde_json::Error>().is_some() {
+            return false;
+        }
+        if let Some(e) = cause.downcast_ref::<reqwest::Error>() {
+            return !e.is_decode();
+        }
+    }
+    true
+}
+
+/// 指数退避重试策略
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Type)]
+pub struct RetryPolicy {
+    /// 总尝试次数（含第一次）
+    pub max_attempts: u32,
+    pub base_delay_ms: u32,
+    pub max_delay_ms: u32,
+    /// 抖动比例，0.2 表示 ±20%
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            base_delay_ms: 5_000,
+            max_delay_ms: 300_000,
+            jitter: 0.2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 第 attempt 次失败后的等待时间
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let exp = attempt.saturating_sub(1).min(31);
+        let raw = (self.base_delay_ms as u64)
+            .saturating_mul(1u64 << exp)
+            .min(self.max_delay_ms as u64);
+        Duration::from_millis((raw as f64 * self.jitter_factor()) as u64)
+    }
+
+    /// 计算下一次尝试的时间，不应再重试时返回 None
+    pub fn next_attempt(&self, attempt: u32, err: &anyhow::Error) -> Option<DateTime<Utc>> {
+        if attempt >= self.max_attempts || !is_retryable(err) {
+            return None;
+        }
+        let delay = chrono::Duration::from_std(self.delay(attempt)).ok()?;
+        Some(Utc::now() + delay)
+    }
+
+    fn jitter_factor(&self) -> f64 {
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        // RandomState 每次实例化的种子都不同，足够用来做抖动
+        let r = RandomState::new().build_hasher().finish();
+        let unit = (r >> 11) as f64 / (1u64 << 53) as f64;
+        1.0 - jitter + unit * 2.0 * jitter
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+
+    #[test]
+    fn test_delay_grows_and_caps() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay_ms: 1_000,
+            max_delay_ms: 10_000,
+            jitter: 0.0,
+        };
+        assert_eq!(policy.delay(1), Duration::from_millis(1_000));
+        assert_eq!(policy.delay(2), Duration::from_millis(2_000));
+        assert_eq!(policy.delay(3), Duration::from_millis(4_000));
+        assert_eq!(policy.delay(8), Duration::from_millis(10_000));
+    }
+
+    #[test]
+    fn test_jitter_stays_in_range() {
+        let policy = RetryPolicy {
+            jitter: 0.5,
+            ..RetryPolicy::default()
+        };
+        for _ in 0..100 {
+            let ms = policy.delay(1).as_millis();
+            assert!((2_500..=7_500).contains(&ms), "delay {} out of range", ms);
+        }
+    }
+
+    #[test]
+    fn test_classification() {
+        let policy = RetryPolicy::default();
+        let too_many = anyhow::Error::from(TaskError::Http {
+            status: 429,
+            url: "https://x.com".into(),
+        });
+        let parse = anyhow::Error::from(TaskError::Parse("bad json".into()));
+        let not_found = anyhow::Error::from(TaskError::Http {
+            status: 404,
+            url: "https://x.com".into(),
+        });
+        assert!(policy.next_attempt(1, &too_many).is_some());
+        assert!(policy.next_attempt(1, &parse).is_none());
+        assert!(policy.next_attempt(1, &not_found).is_none());
+        assert!(policy
+            .next_attempt(1, &anyhow!("connection reset"))
+            .is_some());
+        assert!(policy.next_attempt(5, &too_many).is_none());
+    }
+}
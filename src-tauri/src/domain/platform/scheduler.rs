@@ -1,48 +1,79 @@
+use crate::database::Crud;
 use crate::utils::event::WINDOW_READY;
 use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
 use std::{
+    collections::{HashMap, HashSet},
     sync::{Arc, LazyLock, Mutex},
     time::Duration,
 };
 
 use super::{
     job::{Job, Mission},
+    pause::{self, PauseScope},
+    queue::PriorityQueue,
+    retry::{self, RetryPolicy},
+    sink::{AppEvent, EventSink},
     Task,
 };
 use specta::Type;
 use surrealdb::RecordId;
-use tauri::AppHandle;
 use tauri_specta::Event;
 use tokio::sync::{
     mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender},
-    Semaphore,
+    Notify, OwnedSemaphorePermit, Semaphore,
 };
+use tokio_util::sync::CancellationToken;
 
-pub static SCHEDULER_PAUSED: AtomicBool = AtomicBool::new(false);
-
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Type)]
 pub enum Status {
     Pending,
     Running,
     Succeeded,
+    /// 失败，等待下一次重试
     Failed,
+    /// 重试耗尽或不可重试，等待人工处理
+    DeadLetter,
+    Cancelled,
 }
 
 pub struct HandleSignal {
     pub value: Value,
 }
 
+/// 并发分组，同名 lane 共享同一个并发上限
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Lane {
+    pub name: &'static str,
+    pub limit: usize,
+}
+
 #[async_trait::async_trait]
 pub trait Schedulable: Send + Sync + Clone + 'static {
     fn id(&self) -> RecordId;
     fn status(&self) -> Status;
     fn retry_count(&self) -> u32;
+    /// 数值越大越先执行
+    fn priority(&self) -> u8;
+    fn lane(&self) -> Lane;
+    /// 整个调度器的并发上限
+    fn max_concurrency() -> usize;
+    /// 整个调度器对应的暂停范围
+    fn pause_scope() -> PauseScope;
+    fn retry_policy(&self) -> RetryPolicy;
+    fn next_attempt_at(&self) -> Option<DateTime<Utc>>;
+    fn set_retry(&mut self, retry_count: u32, next_attempt_at: Option<DateTime<Utc>>);
+    /// 使用的 X 账号，为空表示默认账号或不需要凭证
+    fn account(&self) -> Option<i64> {
+        None
+    }
     async fn update_status(&self, status: Status, extra: Option<Value>) -> Result<()>;
-    async fn handle(self) -> Result<Option<HandleSignal>>;
+    async fn handle(self, cancel: CancellationToken) -> Result<Option<HandleSignal>>;
     async fn load_pending() -> Result<Vec<Self>>;
     async fn delete(self) -> Result<()>;
     async fn on_success(self, signal: Option<HandleSignal>) -> Result<()>;
@@ -50,72 +81,292 @@ pub trait Schedulable: Send + Sync + Clone + 'static {
 
 pub struct Scheduler<T: Schedulable> {
     tx: UnboundedSender<T>,
-    pub app: AppHandle,
+    pub sink: Arc<dyn EventSink>,
     clear_flag: Arc<AtomicBool>,
+    sema: Arc<Semaphore>,
+    lanes: Mutex<HashMap<&'static str, Arc<Semaphore>>>,
+    released: Arc<Notify>,
+    limited_until: Mutex<Option<DateTime<Utc>>>,
+    running: Mutex<HashMap<RecordId, CancellationToken>>,
+    /// 分页任务在 handle 中登记的下一页，本次成功结束后派发
+    continuations: Mutex<HashMap<RecordId, T>>,
+    /// 因账号凭证失效而搁置的任务，按账号恢复时重新入队
+    parked: Mutex<Vec<(i64, T)>>,
+    cancelled: Mutex<HashSet<RecordId>>,
+    /// 当前排在内存队列里的数量，供统计使用
+    queued: AtomicUsize,
+    /// 启动以来成功完成的数量
+    succeeded: AtomicU64,
+    /// 退出中，不再派发新任务
+    closing: AtomicBool,
 }
 
 impl<T: Schedulable> Scheduler<T> {
-    pub fn new(app: AppHandle) -> (Arc<Self>, UnboundedReceiver<T>) {
+    pub fn new(sink: Arc<dyn EventSink>) -> (Arc<Self>, UnboundedReceiver<T>) {
         let (tx, rx) = unbounded_channel::<T>();
         let clear_flag = Arc::new(AtomicBool::new(false));
         let sched = Arc::new(Self {
             tx,
-            app,
+            sink,
             clear_flag,
+            sema: Arc::new(Semaphore::new(T::max_concurrency())),
+            lanes: Mutex::new(HashMap::new()),
+            released: Arc::new(Notify::new()),
+            limited_until: Mutex::new(None),
+            running: Mutex::new(HashMap::new()),
+            continuations: Mutex::new(HashMap::new()),
+            parked: Mutex::new(Vec::new()),
+            cancelled: Mutex::new(HashSet::new()),
+            queued: AtomicUsize::new(0),
+            succeeded: AtomicU64::new(0),
+            closing: AtomicBool::new(false),
         });
         (sched, rx)
     }
 
+    fn lane_permit(&self, lane: Lane) -> Option<OwnedSemaphorePermit> {
+        let sema = self
+            .lanes
+            .lock()
+            .unwrap()
+            .entry(lane.name)
+            .or_insert_with(|| Arc::new(Semaphore::new(lane.limit)))
+            .clone();
+        sema.try_acquire_owned().ok()
+    }
+
+    pub fn emit(&self, event: impl Into<AppEvent>) -> Result<()> {
+        self.sink.emit(event.into())
+    }
+
+    /// 限流期间整个调度器暂停派发，到 until 后自动恢复
+    pub fn limit_until(&self, until: DateTime<Utc>) {
+        let mut guard = self.limited_until.lock().unwrap();
+        if guard.is_some_and(|cur| cur >= until) {
+            return;
+        }
+        *guard = Some(until);
+        drop(guard);
+        self.emit(SchedulerRateLimitEvent {
+            limited: true,
+            until: Some(until.to_rfc3339()),
+        })
+        .ok();
+    }
+
+    /// 仍在限流中时返回剩余等待时间，到期后解除并通知前端
+    fn rate_limit_wait(&self) -> Option<Duration> {
+        let mut guard = self.limited_until.lock().unwrap();
+        let until = (*guard)?;
+        match (until - Utc::now()).to_std() {
+            Ok(wait) => Some(wait),
+            Err(_) => {
+                *guard = None;
+                drop(guard);
+                self.emit(SchedulerRateLimitEvent {
+                    limited: false,
+                    until: None,
+                })
+                .ok();
+                None
+            }
+        }
+    }
+
     pub async fn start(self: Arc<Self>, mut rx: UnboundedReceiver<T>, pending: Option<Vec<T>>) {
         let tx = self.tx.clone();
         let pending = pending.unwrap_or(T::load_pending().await.expect("加载 Pending 失败"));
         for item in pending {
-            if let Err(e) = tx.send(item) {
-                tracing::error!("无法发送初始化任务: {}", e);
-            }
+            send_when_due(tx.clone(), item);
         }
-        let clear_flag = self.clear_flag.clone();
+        let sched = self.clone();
 
         // Worker loop
         tokio::spawn(async move {
-            while let Some(item) = rx.recv().await {
-                if clear_flag.load(Ordering::SeqCst) {
-                    // 快速drain掉当前所有剩余任务
-                    while rx.try_recv().is_ok() {} // try_recv直到队空
-                    clear_flag.store(false, Ordering::SeqCst);
+            let mut queue = PriorityQueue::<T>::new();
+            loop {
+                while let Ok(item) = rx.try_recv() {
+                    queue.push(item);
+                }
+                sched.queued.store(queue.len(), Ordering::Relaxed);
+                if sched.closing.load(Ordering::SeqCst) {
+                    // 未派发的保持原状态留在库里，下次启动再加载
+                    break;
+                }
+                if sched.clear_flag.load(Ordering::SeqCst) {
+                    // 快速丢弃当前所有排队任务
+                    queue.clear();
+                    sched.clear_flag.store(false, Ordering::SeqCst);
                     continue;
                 }
-                while SCHEDULER_PAUSED.load(Ordering::SeqCst) {
+                if queue.is_empty() {
+                    match rx.recv().await {
+                        Some(item) => queue.push(item),
+                        None => break,
+                    }
+                    continue;
+                }
+                if pause::is_paused(&T::pause_scope()) {
                     tokio::time::sleep(Duration::from_millis(200)).await;
+                    continue;
+                }
+                if let Some(wait) = sched.rate_limit_wait() {
+                    tokio::time::sleep(wait.min(Duration::from_secs(1))).await;
+                    continue;
+                }
+
+                let Ok(permit) = sched.sema.clone().acquire_owned().await else {
+                    break;
+                };
+                // 等待期间可能有更高优先级的任务入队
+                while let Ok(item) = rx.try_recv() {
+                    queue.push(item);
+                }
+                let Some((item, lane_permit)) = queue.pop_ready(|lane| {
+                    // 被单独暂停的 lane 视为已满，留在队列里
+                    if pause::is_lane_paused(lane.name) {
+                        return None;
+                    }
+                    sched.lane_permit(lane)
+                }) else {
+                    // 所有 lane 都已满或被暂停，等有任务结束、新任务到来或恢复
+                    drop(permit);
+                    tokio::select! {
+                        Some(item) = rx.recv() => queue.push(item),
+                        _ = sched.released.notified() => {}
+                    }
+                    continue;
+                };
+
+                // 运行期间混进队列的重复项直接丢弃，同一个 id 不会并发执行
+                if sched.is_running(&item.id()) {
+                    continue;
+                }
+                if let Some(account) = item.account().filter(|a| pause::is_account_paused(*a)) {
+                    sched.parked.lock().unwrap().push((account, item));
+                    continue;
+                }
+                let id = item.id();
+                if sched.cancelled.lock().unwrap().remove(&id) {
+                    item.update_status(
+                        Status::Cancelled,
+                        Some(json!({ "finished_at": Utc::now() })),
+                    )
+                    .await
+                    .ok();
+                    continue;
                 }
+                let cancel = CancellationToken::new();
+                sched
+                    .running
+                    .lock()
+                    .unwrap()
+                    .insert(id.clone(), cancel.clone());
+
                 let tx_inner = tx.clone();
+                let sched_inner = sched.clone();
                 tokio::spawn(async move {
-                    static WORK_SEMA: LazyLock<Semaphore> = LazyLock::new(|| Semaphore::new(64));
-                    let _permit = WORK_SEMA.acquire().await.unwrap();
-
                     item.update_status(Status::Running, None).await.ok();
+                    let result = item.clone().handle(cancel.clone()).await;
+                    let next = {
+                        let mut running = sched_inner.running.lock().unwrap();
+                        running.remove(&id);
+                        sched_inner.continuations.lock().unwrap().remove(&id)
+                    };
+                    drop(lane_permit);
+                    drop(permit);
+                    sched_inner.released.notify_one();
 
-                    match item.clone().handle().await {
+                    match result {
                         Ok(sig) => {
+                            sched_inner.succeeded.fetch_add(1, Ordering::Relaxed);
                             item.on_success(sig).await.ok();
+                            // 分页任务在 handle 中登记了下一页，这里接着派发
+                            if let Some(next) = next.filter(|_| !cancel.is_cancelled()) {
+                                next.update_status(Status::Pending, None).await.ok();
+                                send_when_due(tx_inner, next);
+                            }
                         }
-                        Err(err) => {
-                            let rc = item.retry_count() + 1;
+                        Err(err) if retry::is_cancelled(&err) => {
                             item.update_status(
-                                Status::Failed,
-                                Some(json!({"error": err.to_string(), "retry_count": rc})),
+                                Status::Cancelled,
+                                Some(json!({ "finished_at": Utc::now() })),
                             )
                             .await
                             .ok();
+                        }
+                        Err(err) => {
+                            if let Some(until) = retry::rate_limited_until(&err) {
+                                // 限流不算失败，等额度恢复后原样重试
+                                sched_inner.limit_until(until);
+                                item.update_status(
+                                    Status::Pending,
+                                    Some(json!({
+                                        "error": err.to_string(),
+                                        "next_attempt_at": until,
+                                    })),
+                                )
+                                .await
+                                .ok();
+                                let mut item = item;
+                                let rc = item.retry_count();
+                                item.set_retry(rc, Some(until));
+                                send_when_due(tx_inner, item);
+                                return;
+                            }
+                            if let Some(account) = retry::unauthorized_account(&err) {
+                                // 凭证失效：只暂停该账号，任务保持 Pending 搁置，重新登录并恢复账号后原样继续；
+                                // 不知道账号时退回暂停整个调度器
+                                let account = account.or(item.account());
+                                let scope = match account {
+                                    Some(id) => PauseScope::Account(id.to_string()),
+                                    None => T::pause_scope(),
+                                };
+                                if let Ok(true) = pause::set_paused(scope.clone(), true).await {
+                                    sched_inner
+                                        .emit(SchedulerPauseEvent {
+                                            scope,
+                                            paused: true,
+                                        })
+                                        .ok();
+                                }
+                                item.update_status(
+                                    Status::Pending,
+                                    Some(json!({ "error": err.to_string() })),
+                                )
+                                .await
+                                .ok();
+                                match account {
+                                    Some(id) => sched_inner.parked.lock().unwrap().push((id, item)),
+                                    None => send_when_due(tx_inner, item),
+                                }
+                                return;
+                            }
+                            let rc = item.retry_count() + 1;
+                            let next_at = item.retry_policy().next_attempt(rc, &err);
+                            let mut extra = json!({
+                                "error": err.to_string(),
+                                "retry_count": rc,
+                                "next_attempt_at": next_at,
+                            });
+                            let status = match next_at {
+                                Some(_) => Status::Failed,
+                                None => {
+                                    extra["finished_at"] = json!(Utc::now());
+                                    Status::DeadLetter
+                                }
+                            };
+                            item.update_status(status, Some(extra)).await.ok();
                             println!(
                                 "任务失败: {:?}, 重试次数: {}, err: {}",
                                 item.id().key(),
                                 rc,
                                 err
                             );
-                            if rc < 5 {
-                                tokio::time::sleep(Duration::from_secs(5)).await;
-                                let _ = tx_inner.send(item);
+                            if next_at.is_some() {
+                                let mut item = item;
+                                item.set_retry(rc, next_at);
+                                send_when_due(tx_inner, item);
                             }
                         }
                     }
@@ -124,20 +375,119 @@ impl<T: Schedulable> Scheduler<T> {
         });
     }
 
+    fn is_running(&self, id: &RecordId) -> bool {
+        self.running.lock().unwrap().contains_key(id)
+    }
+
+    /// 入队；同 id 正在运行时视为重复请求直接忽略，已在排队时由队列合并并提升优先级
     pub fn enqueue(&self, item: T) {
-        let _ = self.tx.send(item);
+        if self.closing.load(Ordering::SeqCst) || self.is_running(&item.id()) {
+            return;
+        }
+        // 重新入队视为新的请求，撤销之前对同 id 的取消
+        self.cancelled.lock().unwrap().remove(&item.id());
+        send_when_due(self.tx.clone(), item);
+    }
+
+    /// 在 handle 中登记下一页，本次成功结束且未被取消时再派发
+    ///
+    /// 不在运行中时（比如在 handle 之外调用）直接入队。
+    pub fn continue_after(&self, item: T) {
+        let running = self.running.lock().unwrap();
+        if running.contains_key(&item.id()) {
+            self.continuations.lock().unwrap().insert(item.id(), item);
+            return;
+        }
+        drop(running);
+        self.enqueue(item);
+    }
+
+    /// 取消指定任务：运行中的通过 token 中断，排队中的在派发时跳过；返回是否正在运行
+    pub fn cancel(&self, id: RecordId) -> bool {
+        if let Some(token) = self.running.lock().unwrap().get(&id) {
+            token.cancel();
+            return true;
+        }
+        self.cancelled.lock().unwrap().insert(id);
+        false
     }
 
     pub fn clear_pending(&self) {
         self.clear_flag.store(true, Ordering::SeqCst);
     }
+
+    /// 停止派发，并在 grace 内等待运行中的任务结束
+    ///
+    /// 超时仍未结束的不强制取消，保持 Running 留给下次启动的恢复流程处理。
+    pub async fn shutdown(&self, grace: Duration) {
+        self.closing.store(true, Ordering::SeqCst);
+        // 唤醒可能卡在等待 lane 的 worker
+        self.released.notify_one();
+        let deadline = tokio::time::Instant::now() + grace;
+        while self.running_count() > 0 && tokio::time::Instant::now() < deadline {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    /// 账号恢复后，把因它搁置的任务重新入队
+    pub fn unpark(&self, account: i64) {
+        let items = {
+            let mut parked = self.parked.lock().unwrap();
+            let (items, rest) = std::mem::take(&mut *parked)
+                .into_iter()
+                .partition::<Vec<_>, _>(|(a, _)| *a == account);
+            *parked = rest;
+            items
+        };
+        // 不走 enqueue，搁置期间用户取消的仍保持取消
+        for (_, item) in items {
+            send_when_due(self.tx.clone(), item);
+        }
+    }
+
+    /// 唤醒等待中的 worker，重新检查可派发的 lane
+    pub fn wake(&self) {
+        self.released.notify_one();
+    }
+
+    pub fn running_count(&self) -> usize {
+        self.running.lock().unwrap().len()
+    }
+
+    pub fn queued_count(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+
+    pub fn succeeded_count(&self) -> u64 {
+        self.succeeded.load(Ordering::Relaxed)
+    }
+}
+
+/// 到达 next_attempt_at 之后再送入队列
+fn send_when_due<T: Schedulable>(tx: UnboundedSender<T>, item: T) {
+    let delay = item
+        .next_attempt_at()
+        .and_then(|at| (at - Utc::now()).to_std().ok());
+    match delay {
+        Some(delay) => {
+            tokio::spawn(async move {
+                tokio::time::sleep(delay).await;
+                let _ = tx.send(item);
+            });
+        }
+        None => {
+            if let Err(e) = tx.send(item) {
+                tracing::error!("无法发送任务: {}", e);
+            }
+        }
+    }
 }
 
 static TASK_SCHED: LazyLock<OnceCell<Arc<Scheduler<Task>>>> = LazyLock::new(|| OnceCell::new());
 impl Scheduler<Task> {
-    pub async fn init(app: AppHandle) -> anyhow::Result<Arc<Self>> {
+    pub async fn init(sink: Arc<dyn EventSink>) -> anyhow::Result<Arc<Self>> {
         // ① 构造 Scheduler，得到 rx
-        let (sched, rx) = Scheduler::<Task>::new(app.clone());
+        let (sched, rx) = Scheduler::<Task>::new(sink);
         // ② 立刻存到全局，不会阻塞
         TASK_SCHED.set(sched.clone()).ok();
         // ③ 异步启动加载和 worker loop
@@ -171,9 +521,9 @@ static JOB_CHECK_REPLY: LazyLock<Mutex<Option<tokio::sync::oneshot::Sender<Vec<J
     LazyLock::new(|| Mutex::new(None));
 static JOB_SCHED: LazyLock<OnceCell<Arc<Scheduler<Job>>>> = LazyLock::new(|| OnceCell::new());
 impl Scheduler<Job> {
-    pub async fn init(app: AppHandle) -> anyhow::Result<Arc<Self>> {
+    pub async fn init(sink: Arc<dyn EventSink>) -> anyhow::Result<Arc<Self>> {
         // ① 构造 Scheduler，得到 rx
-        let (sched, rx) = Scheduler::<Job>::new(app.clone());
+        let (sched, rx) = Scheduler::<Job>::new(sink.clone());
         // ② 立刻存到全局，不会阻塞
         JOB_SCHED.set(sched.clone()).ok();
         // ③ 异步启动加载和 worker loop
@@ -181,7 +531,10 @@ impl Scheduler<Job> {
 
         tokio::spawn(async move {
             let pending_jobs = Job::load_pending().await.expect("加载 Pending 失败");
-            if !pending_jobs.is_empty() {
+            if !sink.interactive() {
+                // 无界面时没人能确认，直接恢复全部
+                sched_clone.start(rx, Some(pending_jobs)).await;
+            } else if !pending_jobs.is_empty() {
                 // 1. emit 事件，告知前端有待确认的 pending
                 let jobs_for_event: Vec<JobCheckEvent> = pending_jobs
                     .iter()
@@ -195,12 +548,12 @@ impl Scheduler<Job> {
                     tokio::time::sleep(Duration::from_secs(1)).await;
                 }
 
-                JobChecksEvent {
-                    jobs: jobs_for_event,
-                }
-                .emit(&app)
-                .map_err(|e| anyhow!("emit JobChecksEvent 失败: {}", e))
-                .ok();
+                sched_clone
+                    .emit(JobChecksEvent {
+                        jobs: jobs_for_event,
+                    })
+                    .map_err(|e| anyhow!("emit JobChecksEvent 失败: {}", e))
+                    .ok();
 
                 // 2. 创建 oneshot channel
                 let (sender, receiver) = tokio::sync::oneshot::channel();
@@ -251,33 +604,262 @@ pub async fn reply_pending_jobs(jobs: Vec<JobCheckEvent>) -> Result<(), String>
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Copy, Clone, Type, Event)]
+#[derive(Serialize, Deserialize, Debug, Clone, Type, Event)]
 pub struct SchedulerPauseEvent {
+    pub scope: PauseScope,
     pub paused: bool,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Type, Event)]
+pub struct SchedulerRateLimitEvent {
+    pub limited: bool,
+    pub until: Option<String>,
+}
+
 #[tauri::command]
 #[specta::specta]
-pub async fn pause_scheduler(app: tauri::AppHandle) -> Result<(), String> {
-    SCHEDULER_PAUSED.store(true, Ordering::SeqCst);
-    SchedulerPauseEvent { paused: true }
-        .emit(&app)
-        .map_err(|e| e.to_string())?;
-    Ok(())
+pub async fn pause_scheduler(scope: PauseScope) -> Result<(), String> {
+    set_pause(scope, true).await
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn resume_scheduler(scope: PauseScope) -> Result<(), String> {
+    set_pause(scope, false).await
 }
 
 #[tauri::command]
 #[specta::specta]
-pub async fn resume_scheduler(app: tauri::AppHandle) -> Result<(), String> {
-    SCHEDULER_PAUSED.store(false, Ordering::SeqCst);
-    SchedulerPauseEvent { paused: false }
-        .emit(&app)
+pub async fn paused_scopes() -> Result<Vec<PauseScope>, String> {
+    Ok(pause::paused_scopes())
+}
+
+async fn set_pause(scope: PauseScope, paused: bool) -> Result<(), String> {
+    let changed = pause::set_paused(scope.clone(), paused)
+        .await
         .map_err(|e| e.to_string())?;
+    if changed {
+        Scheduler::<Task>::get()
+            .map_err(|e| e.to_string())?
+            .emit(SchedulerPauseEvent { scope, paused })
+            .map_err(|e| e.to_string())?;
+    }
     Ok(())
 }
 
+#[tauri::command]
+#[specta::specta]
+/// `target` 为 job id 中 mission 之后的部分，比如按账号扫描时的账号 id
+pub async fn cancel_job(mission: Mission, target: Option<String>) -> Result<(), String> {
+    let id = match target {
+        Some(target) => Job::record_id(format!("{}:{}", mission.as_str(), target)),
+        None => Job::record_id(mission.as_str()),
+    };
+    let running = Scheduler::<Job>::get()
+        .map_err(|e| e.to_string())?
+        .cancel(id.clone());
+    if running {
+        // 运行中的由调度器在 handler 退出后写入最终状态
+        return Ok(());
+    }
+    match mark_cancelled::<Job>(id).await {
+        Ok(true) => Ok(()),
+        _ => Err("任务不存在或已结束".to_string()),
+    }
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn cancel_task(id: String) -> Result<(), String> {
+    let id = RecordId::from_str(&id).map_err(|e| e.to_string())?;
+    let running = Scheduler::<Task>::get()
+        .map_err(|e| e.to_string())?
+        .cancel(id.clone());
+    if running {
+        return Ok(());
+    }
+    match mark_cancelled::<Task>(id).await {
+        Ok(true) => Ok(()),
+        _ => Err("任务不存在或已结束".to_string()),
+    }
+}
+
+/// 只把尚未结束的记录标为 Cancelled，返回是否命中
+///
+/// 与 handler 的收尾并发时不会覆盖已写入的 Succeeded，也不会重建已被删除的 Job。
+async fn mark_cancelled<T: Crud>(id: RecordId) -> Result<bool> {
+    let sql = format!(
+        "UPDATE {id} SET status = '{:?}', finished_at = '{}' WHERE status IN ['{:?}', '{:?}', '{:?}'];",
+        Status::Cancelled,
+        Utc::now().to_rfc3339(),
+        Status::Pending,
+        Status::Running,
+        Status::Failed,
+    );
+    Ok(!T::query_take(&sql, None).await?.is_empty())
+}
+
+/// 应用退出时调用，两个调度器并行等待
+pub async fn shutdown_all(grace: Duration) {
+    let task_sched = Scheduler::<Task>::get();
+    let job_sched = Scheduler::<Job>::get();
+    tokio::join!(
+        async {
+            if let Ok(sched) = &task_sched {
+                sched.shutdown(grace).await;
+            }
+        },
+        async {
+            if let Ok(sched) = &job_sched {
+                sched.shutdown(grace).await;
+            }
+        },
+    );
+}
+
 pub async fn clean_all() -> Result<()> {
     Scheduler::<Task>::get()?.clear_pending();
     Scheduler::<Job>::get()?.clear_pending();
     Ok(())
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::domain::platform::retry::TaskError;
+    use crate::domain::platform::sink::MemorySink;
+
+    #[derive(Clone)]
+    pub(crate) struct Dummy {
+        pub id: i64,
+        /// handle 返回限流错误
+        pub limited: bool,
+        /// handle 中登记一次下一页，模拟分页任务
+        pub requeue: Option<Arc<Scheduler<Dummy>>>,
+        /// handle 执行的时长
+        pub delay: Duration,
+        pub runs: Arc<AtomicUsize>,
+    }
+
+    impl Dummy {
+        pub fn new(id: i64) -> Self {
+            Self {
+                id,
+                limited: false,
+                requeue: None,
+                delay: Duration::ZERO,
+                runs: Arc::new(AtomicUsize::new(0)),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl Schedulable for Dummy {
+        fn id(&self) -> RecordId {
+            RecordId::from(("dummy", self.id))
+        }
+        fn status(&self) -> Status {
+            Status::Pending
+        }
+        fn retry_count(&self) -> u32 {
+            0
+        }
+        fn priority(&self) -> u8 {
+            0
+        }
+        fn lane(&self) -> Lane {
+            Lane {
+                name: "dummy",
+                limit: 2,
+            }
+        }
+        fn max_concurrency() -> usize {
+            2
+        }
+        fn pause_scope() -> PauseScope {
+            // testing 会暂停 Tasks 与 Jobs，这里用一个不会被暂停的范围
+            PauseScope::Account("dummy".to_string())
+        }
+        fn retry_policy(&self) -> RetryPolicy {
+            RetryPolicy::default()
+        }
+        fn next_attempt_at(&self) -> Option<DateTime<Utc>> {
+            None
+        }
+        fn set_retry(&mut self, _: u32, _: Option<DateTime<Utc>>) {}
+        async fn update_status(&self, _: Status, _: Option<Value>) -> Result<()> {
+            Ok(())
+        }
+        async fn handle(self, _: CancellationToken) -> Result<Option<HandleSignal>> {
+            self.runs.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(self.delay).await;
+            if self.limited {
+                return Err(TaskError::RateLimited {
+                    reset_at: Utc::now() + chrono::Duration::hours(1),
+                }
+                .into());
+            }
+            if let Some(sched) = &self.requeue {
+                sched.continue_after(Dummy {
+                    requeue: None,
+                    ..self.clone()
+                });
+            }
+            Ok(None)
+        }
+        async fn load_pending() -> Result<Vec<Self>> {
+            Ok(vec![])
+        }
+        async fn delete(self) -> Result<()> {
+            Ok(())
+        }
+        async fn on_success(self, _: Option<HandleSignal>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_continue_after_running() {
+        let (sched, rx) = Scheduler::<Dummy>::new(MemorySink::new());
+        sched.clone().start(rx, Some(vec![])).await;
+        let item = Dummy {
+            requeue: Some(sched.clone()),
+            ..Dummy::new(1)
+        };
+        let runs = item.runs.clone();
+        sched.enqueue(item);
+
+        for _ in 0..100 {
+            if sched.succeeded_count() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+        assert_eq!(sched.succeeded_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_enqueue_while_running_is_ignored() {
+        let (sched, rx) = Scheduler::<Dummy>::new(MemorySink::new());
+        sched.clone().start(rx, Some(vec![])).await;
+        let item = Dummy {
+            delay: Duration::from_millis(200),
+            ..Dummy::new(2)
+        };
+        let runs = item.runs.clone();
+        sched.enqueue(item.clone());
+        for _ in 0..50 {
+            if sched.running_count() == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        // 另一页扫描又产出了同一个任务
+        sched.enqueue(item);
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
+        assert_eq!(sched.succeeded_count(), 1);
+    }
+}
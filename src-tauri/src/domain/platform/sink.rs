@@ -0,0 +1,155 @@
+#[cfg(test)]
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use tauri::AppHandle;
+use tauri_specta::Event;
+
+use super::api::bookmark::ScanBookmarksEvent;
+use super::api::credentials::AuthStateEvent;
+use super::api::follows::SnapshotFollowsEvent;
+use super::api::list::ScanListEvent;
+use super::api::recheck::RecheckPostsEvent;
+use super::api::search::ScanSearchEvent;
+use super::api::user::ScanLikesEvent;
+use super::api::user_timeline::ScanUserTimelineEvent;
+use super::emitter::AssetDownloadBatchEvent;
+use super::job::ScanLikesIncEvent;
+use super::scheduler::{JobChecksEvent, SchedulerPauseEvent, SchedulerRateLimitEvent};
+use super::stats::SchedulerStatsEvent;
+
+/// 声明任务系统会发出的事件，生成 `AppEvent` 及各事件到它的 `From`
+macro_rules! app_events {
+    ($($variant:ident($ty:ty)),* $(,)?) => {
+        /// 任务系统发往外部的事件
+        #[derive(Debug, Clone)]
+        pub enum AppEvent {
+            $($variant($ty),)*
+        }
+
+        $(
+            impl From<$ty> for AppEvent {
+                fn from(event: $ty) -> Self {
+                    AppEvent::$variant(event)
+                }
+            }
+        )*
+
+        impl AppEvent {
+            fn emit_tauri(&self, app: &AppHandle) -> tauri::Result<()> {
+                match self {
+                    $(AppEvent::$variant(event) => event.emit(app),)*
+                }
+            }
+        }
+    };
+}
+
+app_events! {
+    JobChecks(JobChecksEvent),
+    Pause(SchedulerPauseEvent),
+    RateLimit(SchedulerRateLimitEvent),
+    Stats(SchedulerStatsEvent),
+    AssetDownloadBatch(AssetDownloadBatchEvent),
+    ScanLikes(ScanLikesEvent),
+    ScanLikesInc(ScanLikesIncEvent),
+    ScanBookmarks(ScanBookmarksEvent),
+    ScanUserTimeline(ScanUserTimelineEvent),
+    ScanList(ScanListEvent),
+    ScanSearch(ScanSearchEvent),
+    SnapshotFollows(SnapshotFollowsEvent),
+    RecheckPosts(RecheckPostsEvent),
+    AuthState(AuthStateEvent),
+}
+
+/// 调度器、handler 与 job 对外发事件的唯一出口
+pub trait EventSink: Send + Sync + 'static {
+    fn emit(&self, event: AppEvent) -> Result<()>;
+
+    /// 是否有前端可以响应交互，比如确认要不要恢复上次的 job
+    fn interactive(&self) -> bool {
+        false
+    }
+}
+
+/// 通过 tauri 发给前端
+pub struct TauriSink {
+    app: AppHandle,
+}
+
+impl TauriSink {
+    pub fn new(app: AppHandle) -> Self {
+        Self { app }
+    }
+}
+
+impl EventSink for TauriSink {
+    fn emit(&self, event: AppEvent) -> Result<()> {
+        event.emit_tauri(&self.app)?;
+        Ok(())
+    }
+
+    fn interactive(&self) -> bool {
+        true
+    }
+}
+
+/// 只把事件记在内存里，供测试使用
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemorySink {
+    events: Mutex<Vec<AppEvent>>,
+}
+
+#[cfg(test)]
+impl MemorySink {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self::default())
+    }
+
+    /// 取出目前收到的全部事件
+    pub fn take(&self) -> Vec<AppEvent> {
+        std::mem::take(&mut *self.events.lock().unwrap())
+    }
+}
+
+#[cfg(test)]
+impl EventSink for MemorySink {
+    fn emit(&self, event: AppEvent) -> Result<()> {
+        self.events.lock().unwrap().push(event);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::platform::scheduler::tests::Dummy;
+    use crate::domain::platform::scheduler::Scheduler;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_scheduler_runs_without_webview() {
+        let sink = MemorySink::new();
+        let (sched, rx) = Scheduler::<Dummy>::new(sink.clone());
+        sched.clone().start(rx, Some(vec![])).await;
+        sched.enqueue(Dummy::new(1));
+        sched.enqueue(Dummy {
+            limited: true,
+            ..Dummy::new(2)
+        });
+
+        let mut events = Vec::new();
+        for _ in 0..100 {
+            events.extend(sink.take());
+            if sched.succeeded_count() == 1 && !events.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert_eq!(sched.succeeded_count(), 1);
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, AppEvent::RateLimit(ev) if ev.limited)));
+    }
+}
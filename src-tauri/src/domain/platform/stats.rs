@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use tauri_specta::Event;
+
+use crate::database::enums::table::Table;
+use crate::database::query_take;
+use crate::utils::serialize::i64_to_string;
+
+use super::job::Job;
+use super::scheduler::{Schedulable, Scheduler, Status};
+use super::Task;
+
+/// 启动以来下载的总字节数
+static DOWNLOADED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// 最近一次采样得到的速率，command 直接读取，避免重复计算
+static RATE: LazyLock<Mutex<Rate>> = LazyLock::new(|| Mutex::new(Rate::default()));
+
+/// 采样间隔
+const TICK: Duration = Duration::from_secs(2);
+/// 指数平滑系数，越大越贴近瞬时值
+const SMOOTHING: f64 = 0.3;
+
+pub fn add_downloaded_bytes(n: u64) {
+    DOWNLOADED_BYTES.fetch_add(n, Ordering::Relaxed);
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct Rate {
+    bytes_per_sec: f64,
+    tasks_per_sec: f64,
+}
+
+#[derive(Debug, Deserialize)]
+struct GroupRow {
+    status: Status,
+    #[serde(alias = "mission")]
+    kind: String,
+    count: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct StatusCount {
+    pub status: Status,
+    pub count: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct KindCount {
+    /// TaskKind 或 Mission
+    pub kind: String,
+    pub count: u32,
+    /// 尚未结束（Pending / Running / Failed）的数量
+    pub active: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct SchedulerStats {
+    pub running: u32,
+    pub queued: u32,
+    pub max_concurrency: u32,
+    pub by_status: Vec<StatusCount>,
+    pub by_kind: Vec<KindCount>,
+}
+
+impl SchedulerStats {
+    fn active(&self) -> u32 {
+        self.by_kind.iter().map(|k| k.active).sum()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Type, Event)]
+pub struct SchedulerStatsEvent {
+    pub tasks: SchedulerStats,
+    pub jobs: SchedulerStats,
+    #[serde(serialize_with = "i64_to_string")]
+    #[specta(type = String)]
+    pub downloaded_bytes: i64,
+    pub bytes_per_sec: f64,
+    /// 按最近的完成速率估算剩余任务所需秒数，速率为 0 时无法估算
+    pub eta_secs: Option<u32>,
+}
+
+fn is_active(status: &Status) -> bool {
+    matches!(status, Status::Pending | Status::Running | Status::Failed)
+}
+
+async fn collect<T: Schedulable>(
+    sched: &Scheduler<T>,
+    table: Table,
+    key: &str,
+) -> Result<SchedulerStats> {
+    let sql =
+        format!("SELECT status, {key}, count() AS count FROM {table} GROUP BY status, {key};");
+    let rows: Vec<GroupRow> = query_take(&sql, None).await?;
+
+    let mut by_status: HashMap<Status, u32> = HashMap::new();
+    let mut by_kind: HashMap<String, KindCount> = HashMap::new();
+    for row in rows {
+        *by_status.entry(row.status.clone()).or_default() += row.count;
+        let entry = by_kind
+            .entry(row.kind.clone())
+            .or_insert_with(|| KindCount {
+                kind: row.kind,
+                count: 0,
+                active: 0,
+            });
+        entry.count += row.count;
+        if is_active(&row.status) {
+            entry.active += row.count;
+        }
+    }
+
+    Ok(SchedulerStats {
+        running: sched.running_count() as u32,
+        queued: sched.queued_count() as u32,
+        max_concurrency: T::max_concurrency() as u32,
+        by_status: by_status
+            .into_iter()
+            .map(|(status, count)| StatusCount { status, count })
+            .collect(),
+        by_kind: by_kind.into_values().collect(),
+    })
+}
+
+async fn snapshot() -> Result<SchedulerStatsEvent> {
+    let task_sched = Scheduler::<Task>::get()?;
+    let job_sched = Scheduler::<Job>::get()?;
+    let tasks = collect(&task_sched, Table::Task, "kind").await?;
+    let jobs = collect(&job_sched, Table::Status, "mission").await?;
+    let rate = *RATE.lock().unwrap();
+    let eta_secs = (rate.tasks_per_sec > 0.0)
+        .then(|| (tasks.active() as f64 / rate.tasks_per_sec).ceil() as u32);
+    Ok(SchedulerStatsEvent {
+        tasks,
+        jobs,
+        downloaded_bytes: DOWNLOADED_BYTES.load(Ordering::Relaxed) as i64,
+        bytes_per_sec: rate.bytes_per_sec,
+        eta_secs,
+    })
+}
+
+/// 周期性采样吞吐量并推送统计；空闲时只在刚变空闲那一刻推送一次
+pub fn spawn_stats_loop() {
+    tokio::spawn(async move {
+        let mut last_bytes = DOWNLOADED_BYTES.load(Ordering::Relaxed);
+        let mut last_done = 0u64;
+        let mut last_tick = Instant::now();
+        let mut was_idle = true;
+        let mut interval = tokio::time::interval(TICK);
+        loop {
+            interval.tick().await;
+            let Ok(task_sched) = Scheduler::<Task>::get() else {
+                continue;
+            };
+            let Ok(job_sched) = Scheduler::<Job>::get() else {
+                continue;
+            };
+
+            let elapsed = last_tick.elapsed().as_secs_f64().max(0.001);
+            last_tick = Instant::now();
+            let bytes = DOWNLOADED_BYTES.load(Ordering::Relaxed);
+            let done = task_sched.succeeded_count();
+            {
+                let mut rate = RATE.lock().unwrap();
+                let bps = (bytes - last_bytes) as f64 / elapsed;
+                let tps = (done - last_done) as f64 / elapsed;
+                rate.bytes_per_sec = SMOOTHING * bps + (1.0 - SMOOTHING) * rate.bytes_per_sec;
+                rate.tasks_per_sec = SMOOTHING * tps + (1.0 - SMOOTHING) * rate.tasks_per_sec;
+            }
+            last_bytes = bytes;
+            last_done = done;
+
+            let idle = task_sched.running_count() == 0
+                && task_sched.queued_count() == 0
+                && job_sched.running_count() == 0
+                && job_sched.queued_count() == 0;
+            if idle && was_idle {
+                continue;
+            }
+            was_idle = idle;
+            if idle {
+                *RATE.lock().unwrap() = Rate::default();
+            }
+
+            match snapshot().await {
+                Ok(stats) => {
+                    task_sched.emit(stats).ok();
+                }
+                Err(e) => tracing::error!("统计调度器状态失败: {}", e),
+            }
+        }
+    });
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn scheduler_stats() -> Result<SchedulerStatsEvent, String> {
+    snapshot().await.map_err(|e| e.to_string())
+}
@@ -7,14 +7,75 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use specta::Type;
+use std::str::FromStr;
 use surrealdb::RecordId;
+use tokio_util::sync::CancellationToken;
 
-use super::{handler, Schedulable};
+use super::api::{conversation, space};
+use super::pause::PauseScope;
+use super::retry::RetryPolicy;
+use super::{handler, Lane, Schedulable, Scheduler};
 
-#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash, Type)]
 pub enum TaskKind {
     AssetDownload,
     AssetTransport,
+    ConversationFetch,
+    SpaceFetch,
+}
+
+impl TaskKind {
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            TaskKind::AssetDownload => "asset_download",
+            TaskKind::AssetTransport => "asset_transport",
+            TaskKind::ConversationFetch => "conversation_fetch",
+            TaskKind::SpaceFetch => "space_fetch",
+        }
+    }
+
+    /// 同类任务的并发上限
+    pub const fn concurrency(&self) -> usize {
+        match self {
+            TaskKind::AssetDownload => 16,
+            TaskKind::AssetTransport => 4,
+            // 走 GraphQL 接口，与列表扫描共享额度
+            TaskKind::ConversationFetch | TaskKind::SpaceFetch => 2,
+        }
+    }
+
+    /// 基础优先级，最终优先级还会叠加资源类型
+    pub const fn priority(&self) -> u8 {
+        match self {
+            TaskKind::AssetDownload => 4,
+            TaskKind::AssetTransport => 0,
+            TaskKind::ConversationFetch => 2,
+            TaskKind::SpaceFetch => 3,
+        }
+    }
+
+    pub fn retry_policy(&self) -> RetryPolicy {
+        match self {
+            TaskKind::AssetDownload => RetryPolicy {
+                max_attempts: 5,
+                base_delay_ms: 2_000,
+                max_delay_ms: 300_000,
+                jitter: 0.2,
+            },
+            TaskKind::AssetTransport => RetryPolicy {
+                max_attempts: 3,
+                base_delay_ms: 1_000,
+                max_delay_ms: 30_000,
+                jitter: 0.2,
+            },
+            TaskKind::ConversationFetch | TaskKind::SpaceFetch => RetryPolicy {
+                max_attempts: 3,
+                base_delay_ms: 5_000,
+                max_delay_ms: 600_000,
+                jitter: 0.2,
+            },
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -27,6 +88,12 @@ pub struct Task {
     pub result: Option<Value>,
     pub error: Option<String>,
     pub retry_count: u32,
+    #[serde(default)]
+    pub priority: u8,
+    #[serde(default)]
+    pub retry_policy: RetryPolicy,
+    #[serde(default)]
+    pub next_attempt_at: Option<DateTime<Utc>>,
     pub started_at: Option<DateTime<Utc>>,
     pub finished_at: Option<DateTime<Utc>>,
 }
@@ -38,6 +105,115 @@ impl Task {
     pub async fn get_tasks() -> Result<Vec<Task>> {
         Task::select_all().await.map_err(|e| e.into())
     }
+
+    pub async fn dead_letters() -> Result<Vec<Task>> {
+        let tasks = Task::get_tasks().await?;
+        Ok(tasks
+            .into_iter()
+            .filter(|t| t.status == Status::DeadLetter)
+            .collect())
+    }
+
+    /// 清空重试状态，重新放回调度器
+    pub async fn revive(mut self) -> Result<()> {
+        self.status = Status::Pending;
+        self.error = None;
+        self.retry_count = 0;
+        self.next_attempt_at = None;
+        self.finished_at = None;
+        let task = Task::update(self.id.clone(), self).await?;
+        Scheduler::<Task>::get()?.enqueue(task);
+        Ok(())
+    }
+
+    /// 丢弃任务，可选地把目标资源标记为不可用
+    ///
+    /// 只有下载与转存任务的 tar 是 `DbAsset`，其余类型忽略 `mark_unavailable`。
+    pub async fn discard(self, mark_unavailable: bool) -> Result<()> {
+        let is_asset = matches!(
+            self.kind,
+            TaskKind::AssetDownload | TaskKind::AssetTransport
+        );
+        if mark_unavailable && is_asset {
+            handler::mark_asset_unavailable(self.tar.clone()).await?;
+        }
+        self.delete().await
+    }
+}
+
+/// 死信队列中的任务，供前端展示
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct FailedTask {
+    pub id: String,
+    pub target: String,
+    pub kind: TaskKind,
+    pub error: Option<String>,
+    pub retry_count: u32,
+    pub failed_at: Option<String>,
+}
+
+impl From<Task> for FailedTask {
+    fn from(task: Task) -> Self {
+        Self {
+            id: task.id.to_string(),
+            target: task.tar.to_string(),
+            kind: task.kind,
+            error: task.error,
+            retry_count: task.retry_count,
+            failed_at: task.finished_at.map(|t| t.to_rfc3339()),
+        }
+    }
+}
+
+async fn select_dead_letters(ids: Vec<String>) -> Result<Vec<Task>> {
+    let mut tasks = Vec::with_capacity(ids.len());
+    for id in ids {
+        let id = RecordId::from_str(&id)?;
+        let task = Task::select_record(id).await?;
+        if task.status == Status::DeadLetter {
+            tasks.push(task);
+        }
+    }
+    Ok(tasks)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_failed_tasks() -> Result<Vec<FailedTask>, String> {
+    let tasks = Task::dead_letters().await.map_err(|e| e.to_string())?;
+    Ok(tasks.into_iter().map(FailedTask::from).collect())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn retry_failed_tasks(ids: Vec<String>) -> Result<(), String> {
+    let tasks = select_dead_letters(ids).await.map_err(|e| e.to_string())?;
+    for task in tasks {
+        task.revive().await.map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn retry_all_failed_tasks() -> Result<(), String> {
+    let tasks = Task::dead_letters().await.map_err(|e| e.to_string())?;
+    for task in tasks {
+        task.revive().await.map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn discard_failed_tasks(ids: Vec<String>, mark_unavailable: bool) -> Result<(), String> {
+    let tasks = select_dead_letters(ids).await.map_err(|e| e.to_string())?;
+    for task in tasks {
+        task.discard(mark_unavailable)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
 }
 
 #[async_trait::async_trait]
@@ -51,6 +227,31 @@ impl Schedulable for Task {
     fn retry_count(&self) -> u32 {
         self.retry_count
     }
+    fn priority(&self) -> u8 {
+        self.priority
+    }
+    fn lane(&self) -> Lane {
+        Lane {
+            name: self.kind.as_str(),
+            limit: self.kind.concurrency(),
+        }
+    }
+    fn max_concurrency() -> usize {
+        64
+    }
+    fn pause_scope() -> PauseScope {
+        PauseScope::Tasks
+    }
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+    }
+    fn next_attempt_at(&self) -> Option<DateTime<Utc>> {
+        self.next_attempt_at
+    }
+    fn set_retry(&mut self, retry_count: u32, next_attempt_at: Option<DateTime<Utc>>) {
+        self.retry_count = retry_count;
+        self.next_attempt_at = next_attempt_at;
+    }
     async fn delete(self) -> Result<()> {
         let mut last_err = None;
         for _ in 0..5 {
@@ -78,13 +279,19 @@ impl Schedulable for Task {
     }
 
     async fn load_pending() -> Result<Vec<Self>> {
-        Task::get_tasks().await
+        let tasks = Task::get_tasks().await?;
+        Ok(tasks
+            .into_iter()
+            .filter(|t| !matches!(t.status, Status::DeadLetter | Status::Cancelled))
+            .collect())
     }
 
-    async fn handle(self) -> Result<Option<super::HandleSignal>> {
+    async fn handle(self, cancel: CancellationToken) -> Result<Option<super::HandleSignal>> {
         match self.kind {
-            TaskKind::AssetDownload => handler::download_asset(self).await.map_err(|e| e.into()),
-            TaskKind::AssetTransport => handler::transport_asset(self).await.map_err(|e| e.into()),
+            TaskKind::AssetDownload => handler::download_asset(self, cancel).await,
+            TaskKind::AssetTransport => handler::transport_asset(self, cancel).await,
+            TaskKind::ConversationFetch => conversation::fetch_conversation(self, cancel).await,
+            TaskKind::SpaceFetch => space::fetch_space(self, cancel).await,
         }
     }
 }
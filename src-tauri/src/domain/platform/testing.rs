@@ -0,0 +1,64 @@
+//! 测试共用的运行环境：内存数据库、临时保存目录与记录事件的调度器
+//!
+//! surrealdb 的本地引擎绑定在创建它的 runtime 上，所以用到数据库的测试都通过
+//! `run` 跑在同一个 runtime 里，并且逐个执行，避免共享的库与事件互相干扰。
+
+use std::future::Future;
+use std::sync::{Arc, LazyLock, Mutex};
+
+use tokio::runtime::Runtime;
+use tokio::sync::OnceCell;
+
+use crate::database::core::init_mem_db;
+use crate::database::enums::meta::MetaKey;
+use crate::database::Crud;
+use crate::domain::models::meta::{DbMeta, GlobalVal};
+
+use super::job::Job;
+use super::pause::{self, PauseScope};
+use super::sink::MemorySink;
+use super::{Scheduler, Task};
+
+static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .expect("创建测试 runtime 失败")
+});
+static SERIAL: Mutex<()> = Mutex::new(());
+static SINK: LazyLock<Arc<MemorySink>> = LazyLock::new(MemorySink::new);
+static READY: OnceCell<()> = OnceCell::const_new();
+
+/// 初始化内存库与两个调度器；调度器处于暂停状态，测试直接调用 handler
+async fn setup() {
+    init_mem_db().await.expect("初始化内存数据库失败");
+    let save_dir = std::env::temp_dir().join(format!("crab-test-{}", std::process::id()));
+    std::fs::create_dir_all(&save_dir).expect("创建保存目录失败");
+    DbMeta::new(
+        MetaKey::SaveDir.as_str().to_string(),
+        save_dir.to_string_lossy().to_string(),
+    )
+    .upsert()
+    .await
+    .expect("写入保存目录失败");
+    GlobalVal::update().await.expect("加载保存目录失败");
+    pause::set_paused(PauseScope::Tasks, true).await.ok();
+    pause::set_paused(PauseScope::Jobs, true).await.ok();
+    Scheduler::<Task>::init(SINK.clone()).await.ok();
+    Scheduler::<Job>::init(SINK.clone()).await.ok();
+}
+
+/// 在共用环境中执行测试，开始前清掉之前测试留下的事件
+pub fn run<F: Future>(test: F) -> F::Output {
+    let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+    RUNTIME.block_on(async {
+        READY.get_or_init(setup).await;
+        SINK.take();
+        test.await
+    })
+}
+
+/// 调度器与 handler 发出的事件
+pub fn sink() -> Arc<MemorySink> {
+    SINK.clone()
+}
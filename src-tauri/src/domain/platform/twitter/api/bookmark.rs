@@ -0,0 +1,64 @@
+use super::requests::user;
+use super::timeline::{create_timeline_job, job_account, process_timeline_chunk};
+use crate::database::Crud;
+use crate::domain::models::twitter::bookmark::{BookmarkedPost, DbBookmark};
+use crate::domain::platform::job::{Job, Mission};
+use crate::domain::platform::scheduler::Scheduler;
+use crate::domain::platform::twitter::auth::account::resolve_account;
+use crate::domain::platform::HandleSignal;
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use specta::Type;
+use tauri_specta::Event;
+use tokio_util::sync::CancellationToken;
+
+/// 扫描指定账号，未指定时为默认账号；每个账号各有一个 job
+#[tauri::command]
+#[specta::specta]
+pub async fn scan_bookmarks_timeline(account: Option<String>) -> Result<(), String> {
+    let account = resolve_account(account).await.map_err(|e| e.to_string())?;
+    let intersection = DbBookmark::select_pagin(Some(account), 200, None)
+        .await
+        .map_err(|e| e.to_string())?;
+    let ids = intersection.into_iter().map(|p| p.post).collect::<Vec<_>>();
+    create_timeline_job(
+        Job::record_id(format!("{}:{}", Mission::ScanBookmarks.as_str(), account)),
+        Mission::ScanBookmarks,
+        json!({ "cursor": null, "account": account.to_string() }),
+        ids,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Type, Event)]
+pub struct ScanBookmarksEvent {
+    /// 账号 id，旧 job 没有记录账号时为空
+    pub account: Option<String>,
+    pub count: u32,
+    pub running: bool,
+}
+
+pub async fn process_bookmarks_chunk(
+    job: Job,
+    cancel: CancellationToken,
+) -> Result<Option<HandleSignal>> {
+    let account = job_account(&job.params).map(|a| a.to_string());
+    let progress = process_timeline_chunk::<BookmarkedPost, _>(job, cancel, |_, cursor| {
+        Ok(user::bookmarks(None, cursor))
+    })
+    .await?;
+
+    Scheduler::<Job>::get()?
+        .emit(ScanBookmarksEvent {
+            account,
+            count: progress.count,
+            running: !progress.is_end,
+        })
+        .map_err(|e| anyhow!("emit ScanBookmarksEvent 失败: {}", e))?;
+    Ok(Some(HandleSignal {
+        value: json!({ "is_end": progress.is_end }),
+    }))
+}
@@ -0,0 +1,216 @@
+use super::requests::tweet;
+use super::timeline::send_graphql;
+use crate::database::Crud;
+use crate::domain::models::twitter::entities::DbEntitie;
+use crate::domain::models::twitter::post::{Conversation, DbPost, DbReply, Post, QuotePost, Reply};
+use crate::domain::platform::retry::TaskError;
+use crate::domain::platform::scheduler::{self, Scheduler};
+use crate::domain::platform::twitter::auth::auth::{self, AuthGenerator};
+use crate::domain::platform::{handle_entities, HandleSignal, Schedulable, Task, TaskKind};
+
+use anyhow::{Context, Result};
+use futures::future::join_all;
+use serde_json::{json, Value};
+use surrealdb::RecordId;
+use tokio_util::sync::CancellationToken;
+
+/// 一次任务最多翻的页数，热门推文的回复可能有成千上万条
+const MAX_PAGES: usize = 5;
+
+const INSTRUCTIONS: &str = "/data/threaded_conversation_with_injections_v2/instructions";
+
+/// 一页 TweetDetail 的解析结果
+struct ConversationPage {
+    focal: Option<Post>,
+    conversations: Vec<Conversation>,
+    next: Option<String>,
+}
+
+fn parse_page(json: &Value, focal_id: i64) -> Result<ConversationPage> {
+    let instructions = json
+        .pointer(INSTRUCTIONS)
+        .and_then(Value::as_array)
+        .context("can not find threaded_conversation instructions")?;
+    let entries = instructions
+        .iter()
+        .filter(|ins| ins.get("type").and_then(Value::as_str) == Some("TimelineAddEntries"))
+        .filter_map(|ins| ins.get("entries").and_then(Value::as_array))
+        .flatten();
+
+    let mut page = ConversationPage {
+        focal: None,
+        conversations: Vec::new(),
+        next: None,
+    };
+    for entry in entries {
+        let entry_id = entry.get("entryId").and_then(Value::as_str).unwrap_or("");
+        if entry_id.starts_with("tweet-") {
+            // 祖先推文也以 tweet- 开头，只取焦点推文
+            if let Some(post) = entry
+                .pointer("/content/itemContent/tweet_results/result")
+                .and_then(Post::from_api)
+                .filter(|p| p.rest_id == focal_id)
+            {
+                page.focal = Some(post);
+            }
+        } else if entry_id.starts_with("conversationthread-") {
+            let replies = entry
+                .pointer("/content/items")
+                .and_then(Value::as_array)
+                .map(|items| {
+                    items
+                        .iter()
+                        .filter_map(|item| {
+                            item.pointer("/item/itemContent/tweet_results/result")
+                                .and_then(QuotePost::from_api)
+                        })
+                        .map(Reply)
+                        .collect::<Vec<_>>()
+                })
+                .unwrap_or_default();
+            if !replies.is_empty() {
+                page.conversations.push(Conversation {
+                    conversation: replies,
+                });
+            }
+        } else if entry_id.starts_with("cursor-bottom-") {
+            page.next = entry
+                .pointer("/content/itemContent/value")
+                .and_then(Value::as_str)
+                .map(|s| s.to_string());
+        }
+    }
+    Ok(page)
+}
+
+/// 为一条推文建立抓取回复会话的任务
+pub fn conversation_task(post: RecordId) -> Task {
+    let kind = TaskKind::ConversationFetch;
+    let retry_policy = kind.retry_policy();
+    Task {
+        id: Task::record_id(format!("conversation:{}", post.key())),
+        tar: post,
+        priority: kind.priority(),
+        kind,
+        payload: None,
+        status: scheduler::Status::Pending,
+        result: None,
+        error: None,
+        retry_count: 0,
+        retry_policy,
+        next_attempt_at: None,
+        started_at: None,
+        finished_at: None,
+    }
+}
+
+/// 拉取推文下的回复会话，回复存入 reply 表，作者头像与媒体交给下载任务
+pub async fn fetch_conversation(
+    task: Task,
+    cancel: CancellationToken,
+) -> Result<Option<HandleSignal>> {
+    task.update_status(scheduler::Status::Running, None)
+        .await
+        .ok();
+    let focal_id = task
+        .tar
+        .key()
+        .to_string()
+        .parse::<i64>()
+        .context("conversation task target is not a post id")?;
+
+    let mut cred = auth::UserAuth.generate().await?;
+    let mut focal = None;
+    let mut conversations = Vec::new();
+    let mut cursor = None;
+    for _ in 0..MAX_PAGES {
+        let req = tweet::tweet_detail(focal_id.to_string(), cursor.take());
+        let (json_data, rate_limit) = send_graphql(&mut cred, req, &cancel).await?;
+        let page = parse_page(&json_data, focal_id).map_err(|e| TaskError::Parse(e.to_string()))?;
+        focal = focal.or(page.focal);
+        let empty = page.conversations.is_empty();
+        conversations.extend(page.conversations);
+        match page.next {
+            Some(next) if !empty && !rate_limit.is_exhausted() => cursor = Some(next),
+            _ => break,
+        }
+    }
+
+    // 焦点推文可能已被删除，此时沿用库里的版本
+    let mut post = match focal {
+        Some(post) => post,
+        None => DbPost::get(task.tar.clone())
+            .await
+            .map_err(|e| TaskError::Parse(format!("focal tweet missing: {}", e)))?,
+    };
+    post.replies = Some(conversations);
+    let db_post = post.clone().into_db();
+
+    let checked_tasks =
+        handle_entities(DbEntitie::from_post(post, TaskKind::AssetDownload)).await?;
+    // 帖子本身已存在时 insert_jump 不会覆盖，回复引用需单独写入
+    DbPost::merge(
+        db_post.id.clone(),
+        json!({ "replies": serde_json::to_value(&db_post.replies)? }),
+    )
+    .await?;
+    let sched = Scheduler::<Task>::get()?;
+    for task in checked_tasks
+        .into_iter()
+        .filter(|t| !matches!(t.status, scheduler::Status::Succeeded))
+    {
+        sched.enqueue(task);
+    }
+    Ok(None)
+}
+
+/// 读取已抓取的回复会话，帖子列表里不带回复，展开时再查
+pub async fn select_conversations(post: RecordId) -> Result<Vec<Conversation>> {
+    let db_post = DbPost::select_record(post).await?;
+    let convs = join_all(
+        db_post
+            .replies
+            .unwrap_or_default()
+            .into_iter()
+            .map(|conv| async {
+                let replies = join_all(conv.conversation.into_iter().map(|id| async move {
+                    let reply =
+                        DbReply::select_record(DbReply::record_id(id.key().clone())).await?;
+                    let mut post = reply.0.into_domain().await?;
+                    post.replies = None;
+                    Ok::<_, anyhow::Error>(Reply(post.to_quote()))
+                }))
+                .await;
+                Conversation {
+                    // 个别回复可能缺失，跳过即可
+                    conversation: replies.into_iter().flatten().collect(),
+                }
+            }),
+    )
+    .await;
+    Ok(convs)
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn fetch_post_conversation(rest_id: String) -> Result<(), String> {
+    let id = rest_id.parse::<i64>().map_err(|e| e.to_string())?;
+    let task = conversation_task(DbPost::record_id(id));
+    let tasks = Task::insert_jump(vec![task])
+        .await
+        .map_err(|e| e.to_string())?;
+    let sched = Scheduler::<Task>::get().map_err(|e| e.to_string())?;
+    for task in tasks {
+        sched.enqueue(task);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn take_post_conversations(rest_id: String) -> Result<Vec<Conversation>, String> {
+    let id = rest_id.parse::<i64>().map_err(|e| e.to_string())?;
+    select_conversations(DbPost::record_id(id))
+        .await
+        .map_err(|e| e.to_string())
+}
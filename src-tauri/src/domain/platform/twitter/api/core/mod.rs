@@ -1 +1,4 @@
 pub mod param_builder;
+pub mod rate_limit;
+pub mod registry;
+pub mod transport;
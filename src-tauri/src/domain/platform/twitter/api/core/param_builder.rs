@@ -4,6 +4,14 @@ use std::marker::PhantomData;
 
 pub trait Flag {
     fn as_str(&self) -> &'static str;
+
+    /// 运行时覆盖的开关，构建时盖过代码里的默认值
+    fn overrides() -> Vec<(String, bool)>
+    where
+        Self: Sized,
+    {
+        Vec::new()
+    }
 }
 
 pub trait Builder<T: Flag> {
@@ -50,7 +58,11 @@ impl<T: Flag> Builder<T> for ParamBuilder<T> {
     }
 
     fn build(&self) -> String {
-        serde_json::to_string(&self.map).expect("Failed to serialize map")
+        let mut map = self.map.clone();
+        for (key, value) in T::overrides() {
+            map.insert(key, Value::Bool(value));
+        }
+        serde_json::to_string(&map).expect("Failed to serialize map")
     }
 }
 
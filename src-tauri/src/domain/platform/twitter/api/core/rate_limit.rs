@@ -0,0 +1,63 @@
+use chrono::{DateTime, Duration, Utc};
+use reqwest::header::HeaderMap;
+
+/// 从响应头解析出的限流信息
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RateLimit {
+    pub limit: Option<u32>,
+    pub remaining: Option<u32>,
+    pub reset: Option<DateTime<Utc>>,
+}
+
+impl RateLimit {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let get = |key: &str| -> Option<i64> {
+            headers
+                .get(key)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|s| s.trim().parse::<i64>().ok())
+        };
+        Self {
+            limit: get("x-rate-limit-limit").map(|v| v as u32),
+            remaining: get("x-rate-limit-remaining").map(|v| v as u32),
+            reset: get("x-rate-limit-reset").and_then(|ts| DateTime::from_timestamp(ts, 0)),
+        }
+    }
+
+    /// 当前窗口的额度是否已用完
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == Some(0)
+    }
+
+    /// 额度恢复时间，没有 reset 头时按 X 的 15 分钟窗口估算
+    pub fn reset_at(&self) -> DateTime<Utc> {
+        self.reset
+            .filter(|at| *at > Utc::now())
+            .unwrap_or_else(|| Utc::now() + Duration::minutes(15))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-rate-limit-limit", HeaderValue::from_static("500"));
+        headers.insert("x-rate-limit-remaining", HeaderValue::from_static("0"));
+        headers.insert("x-rate-limit-reset", HeaderValue::from_static("1750000000"));
+        let limit = RateLimit::from_headers(&headers);
+        assert_eq!(limit.limit, Some(500));
+        assert!(limit.is_exhausted());
+        assert_eq!(limit.reset.map(|t| t.timestamp()), Some(1750000000));
+    }
+
+    #[test]
+    fn test_missing_headers() {
+        let limit = RateLimit::from_headers(&HeaderMap::new());
+        assert!(!limit.is_exhausted());
+        assert!(limit.reset_at() > Utc::now());
+    }
+}
@@ -0,0 +1,253 @@
+//! GraphQL 接口的 query id 与 features 覆盖
+//!
+//! X 的网页每次发版都可能轮换 query id、新增 feature，代码里的值只作默认。
+//! 覆盖依次来自保存目录下的 `graphql_overrides.json` 与 metadata 表，后者优先；
+//! 接口报告缺少的 feature 会自动记入 metadata。
+
+use crate::database::enums::meta::MetaKey;
+use crate::database::Crud;
+use crate::domain::models::meta::{DbMeta, GlobalVal};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use std::collections::BTreeMap;
+use std::sync::{LazyLock, RwLock};
+
+/// 保存目录下的覆盖文件
+pub const OVERRIDES_FILE: &str = "graphql_overrides.json";
+
+const MISSING_FEATURES: &str = "The following features cannot be null:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Likes,
+    Bookmarks,
+    UserTweets,
+    UserTweetsAndReplies,
+    UserMedia,
+    Followers,
+    Following,
+    TweetDetail,
+    TweetResultByRestId,
+    ListLatestTweetsTimeline,
+    ListByRestId,
+    ListMembers,
+    SearchTimeline,
+    AudioSpaceById,
+    Viewer,
+}
+
+impl Operation {
+    pub fn name(self) -> &'static str {
+        match self {
+            Operation::Likes => "Likes",
+            Operation::Bookmarks => "Bookmarks",
+            Operation::UserTweets => "UserTweets",
+            Operation::UserTweetsAndReplies => "UserTweetsAndReplies",
+            Operation::UserMedia => "UserMedia",
+            Operation::Followers => "Followers",
+            Operation::Following => "Following",
+            Operation::TweetDetail => "TweetDetail",
+            Operation::TweetResultByRestId => "TweetResultByRestId",
+            Operation::ListLatestTweetsTimeline => "ListLatestTweetsTimeline",
+            Operation::ListByRestId => "ListByRestId",
+            Operation::ListMembers => "ListMembers",
+            Operation::SearchTimeline => "SearchTimeline",
+            Operation::AudioSpaceById => "AudioSpaceById",
+            Operation::Viewer => "Viewer",
+        }
+    }
+
+    fn default_query_id(self) -> &'static str {
+        match self {
+            Operation::Likes => "eQl7iWsCr2fChppuJdAeRw",
+            Operation::Bookmarks => "-LGfdImKeQz0xS_jjUwzlA",
+            Operation::UserTweets => "E3opETHurmVJflFsUBVuUQ",
+            Operation::UserTweetsAndReplies => "bt4TKuFz4T7Ckk-VvQVSow",
+            Operation::UserMedia => "dexO_2tohK86JDudXXG3Yw",
+            Operation::Followers => "OGScL-RC4DFMsRGOCjPR6g",
+            Operation::Following => "o5eNLkJb03ayTQa97Cpp7w",
+            Operation::TweetDetail => "nBS-WpgA6ZG0CyNHD517JQ",
+            Operation::TweetResultByRestId => "Xl5pC_lBk_gcO2ItU39DQw",
+            Operation::ListLatestTweetsTimeline => "RlZzktZY_9wJynoepm8ZsA",
+            Operation::ListByRestId => "9hbYpeVBMq8-yB8slayGWQ",
+            Operation::ListMembers => "Bnhcen0kdsMAU1tW7U79qQ",
+            Operation::SearchTimeline => "AIdc203rPpK_k_2KWSdm7g",
+            Operation::AudioSpaceById => "rC8xEnZ0Yw7ZbD1Yj7iVIw",
+            Operation::Viewer => "W62NnYgkgziw9bwyoVht0g",
+        }
+    }
+
+    /// 当前生效的 query id，有覆盖时用覆盖的值
+    pub fn query_id(self) -> String {
+        OVERRIDES
+            .read()
+            .unwrap()
+            .query_ids
+            .get(self.name())
+            .cloned()
+            .unwrap_or_else(|| self.default_query_id().to_string())
+    }
+
+    pub fn url(self) -> String {
+        format!(
+            "https://x.com/i/api/graphql/{}/{}",
+            self.query_id(),
+            self.name()
+        )
+    }
+}
+
+/// 以操作名为 key 的 query id，以及按名字覆盖的 feature / fieldToggle 开关
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+pub struct GraphqlOverrides {
+    #[serde(default)]
+    pub query_ids: BTreeMap<String, String>,
+    #[serde(default)]
+    pub features: BTreeMap<String, bool>,
+    #[serde(default)]
+    pub field_toggles: BTreeMap<String, bool>,
+}
+
+impl GraphqlOverrides {
+    fn extend(&mut self, other: GraphqlOverrides) {
+        self.query_ids.extend(other.query_ids);
+        self.features.extend(other.features);
+        self.field_toggles.extend(other.field_toggles);
+    }
+}
+
+static OVERRIDES: LazyLock<RwLock<GraphqlOverrides>> =
+    LazyLock::new(|| RwLock::new(GraphqlOverrides::default()));
+
+pub fn feature_overrides() -> Vec<(String, bool)> {
+    let guard = OVERRIDES.read().unwrap();
+    guard
+        .features
+        .iter()
+        .map(|(k, v)| (k.clone(), *v))
+        .collect()
+}
+
+pub fn field_toggle_overrides() -> Vec<(String, bool)> {
+    let guard = OVERRIDES.read().unwrap();
+    guard
+        .field_toggles
+        .iter()
+        .map(|(k, v)| (k.clone(), *v))
+        .collect()
+}
+
+fn load_file() -> Result<Option<GraphqlOverrides>> {
+    let Some(path) = GlobalVal::get_save_dir().map(|dir| dir.join(OVERRIDES_FILE)) else {
+        return Ok(None);
+    };
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(Some(serde_json::from_str(&content)?))
+}
+
+async fn load_stored() -> Result<GraphqlOverrides> {
+    match DbMeta::get(MetaKey::GraphqlOverrides).await? {
+        Some(v) => Ok(serde_json::from_str(&v.into_string())?),
+        None => Ok(GraphqlOverrides::default()),
+    }
+}
+
+async fn store(overrides: &GraphqlOverrides) -> Result<()> {
+    let data = serde_json::to_string(overrides)?;
+    DbMeta::new(MetaKey::GraphqlOverrides.as_str().to_string(), data)
+        .upsert()
+        .await?;
+    Ok(())
+}
+
+/// 重新读取覆盖文件与 metadata，文件格式错误只警告，不影响 metadata 中的覆盖
+pub async fn init() -> Result<()> {
+    let mut overrides = GraphqlOverrides::default();
+    match load_file() {
+        Ok(Some(file)) => overrides.extend(file),
+        Ok(None) => {}
+        Err(e) => tracing::warn!("读取 {} 失败: {}", OVERRIDES_FILE, e),
+    }
+    overrides.extend(load_stored().await?);
+    *OVERRIDES.write().unwrap() = overrides;
+    Ok(())
+}
+
+/// 从错误响应里取出缺少的 feature 名
+pub fn missing_features(body: &Value) -> Vec<String> {
+    body.get("errors")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.get("message").and_then(Value::as_str))
+        .filter_map(|msg| msg.split_once(MISSING_FEATURES).map(|(_, rest)| rest))
+        .flat_map(|rest| rest.split(','))
+        .map(|name| name.trim().trim_end_matches('.').to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// 记下接口要求的 feature，默认关闭，之后的请求都会带上
+pub async fn learn_features(names: &[String]) -> Result<()> {
+    let mut stored = load_stored().await?;
+    {
+        let mut guard = OVERRIDES.write().unwrap();
+        for name in names {
+            guard.features.entry(name.clone()).or_insert(false);
+            stored.features.entry(name.clone()).or_insert(false);
+        }
+    }
+    store(&stored).await
+}
+
+/// 当前生效的覆盖（文件与 metadata 合并后）
+#[tauri::command]
+#[specta::specta]
+pub async fn get_graphql_overrides() -> Result<GraphqlOverrides, String> {
+    Ok(OVERRIDES.read().unwrap().clone())
+}
+
+/// 写入 metadata 中的覆盖并立即生效
+#[tauri::command]
+#[specta::specta]
+pub async fn set_graphql_overrides(overrides: GraphqlOverrides) -> Result<(), String> {
+    store(&overrides).await.map_err(|e| e.to_string())?;
+    init().await.map_err(|e| e.to_string())
+}
+
+/// 修改覆盖文件后重新加载
+#[tauri::command]
+#[specta::specta]
+pub async fn reload_graphql_overrides() -> Result<(), String> {
+    init().await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_missing_features() {
+        let body = json!({
+            "errors": [{
+                "message": "The following features cannot be null: rweb_new_flag_enabled, other_flag",
+                "code": 336
+            }]
+        });
+        assert_eq!(
+            missing_features(&body),
+            vec![
+                "rweb_new_flag_enabled".to_string(),
+                "other_flag".to_string()
+            ]
+        );
+        assert!(missing_features(&json!({ "errors": [{ "message": "Bad request" }] })).is_empty());
+    }
+}
@@ -0,0 +1,475 @@
+//! X 接口的 HTTP 传输层
+//!
+//! 所有对 X 的请求都经过 [`Transport`]。除直连外支持两种调试模式：
+//! record 把请求与响应成对写入目录，replay 从目录读回，不碰网络，
+//! 用来离线复现用户的扫描问题，或端到端测试解析与入库。
+//! 启动时读取环境变量 `X_TRANSPORT`（`record:<dir>` / `replay:<dir>`），也可运行时切换。
+
+use anyhow::{anyhow, Context, Result};
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use std::path::PathBuf;
+use std::sync::{Arc, LazyLock, RwLock};
+
+use crate::domain::platform::proxy;
+
+pub const TRANSPORT_ENV: &str = "X_TRANSPORT";
+
+/// 录制时不写入文件的请求头
+const SECRET_HEADERS: [&str; 4] = ["authorization", "cookie", "x-csrf-token", "x-guest-token"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpRequest {
+    pub method: String,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<String>,
+}
+
+impl HttpRequest {
+    pub fn new(method: Method, url: Url, headers: &HeaderMap) -> Self {
+        Self {
+            method: method.to_string(),
+            url: url.to_string(),
+            headers: headers
+                .iter()
+                .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+                .collect(),
+            body: None,
+        }
+    }
+
+    /// 回放用的 key：query 中的 JSON 参数按 key 排序后再比较，与 HashMap 的顺序无关
+    fn replay_key(&self) -> Result<String> {
+        let url = Url::parse(&self.url)?;
+        let mut pairs = url
+            .query_pairs()
+            .map(|(k, v)| {
+                let v = serde_json::from_str::<Value>(&v)
+                    .map(|json| json.to_string())
+                    .unwrap_or_else(|_| v.to_string());
+                format!("{k}={v}")
+            })
+            .collect::<Vec<_>>();
+        pairs.sort();
+        Ok(format!(
+            "{} {}{} {} {}",
+            self.method,
+            url.host_str().unwrap_or_default(),
+            url.path(),
+            pairs.join("&"),
+            self.body.as_deref().unwrap_or_default()
+        ))
+    }
+
+    fn redacted(&self) -> Self {
+        let mut req = self.clone();
+        req.headers
+            .retain(|(k, _)| !SECRET_HEADERS.contains(&k.to_ascii_lowercase().as_str()));
+        req
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HttpResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl HttpResponse {
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    pub fn header_map(&self) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (k, v) in &self.headers {
+            if let (Ok(k), Ok(v)) = (
+                HeaderName::from_bytes(k.as_bytes()),
+                HeaderValue::from_str(v),
+            ) {
+                map.append(k, v);
+            }
+        }
+        map
+    }
+
+    pub fn json<T: for<'de> Deserialize<'de>>(&self) -> Result<T> {
+        Ok(serde_json::from_str(&self.body)?)
+    }
+    /// 去掉 `Set-Cookie`，回放时不能用录制时的 cookie 覆盖当前凭证
+    fn redacted(&self) -> Self {
+        let mut resp = self.clone();
+        resp.headers
+            .retain(|(k, _)| !k.eq_ignore_ascii_case("set-cookie"));
+        resp
+    }
+}
+
+#[async_trait]
+pub trait Transport: Send + Sync {
+    async fn send(&self, req: HttpRequest) -> Result<HttpResponse>;
+}
+
+/// 直接走网络，每次请求取当前代理设置下的 client，代理变更即时生效
+pub struct ReqwestTransport;
+
+impl ReqwestTransport {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn send(&self, req: HttpRequest) -> Result<HttpResponse> {
+        let method = Method::from_bytes(req.method.as_bytes())?;
+        let mut builder = proxy::api_client().request(method, &req.url);
+        for (k, v) in &req.headers {
+            builder = builder.header(k, v);
+        }
+        if let Some(body) = req.body {
+            builder = builder.body(body);
+        }
+        let resp = builder.send().await?;
+        let status = resp.status().as_u16();
+        let headers = resp
+            .headers()
+            .iter()
+            .filter_map(|(k, v)| Some((k.to_string(), v.to_str().ok()?.to_string())))
+            .collect();
+        let body = resp.text().await?;
+        Ok(HttpResponse {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+/// 录制或回放的一对请求与响应
+#[derive(Debug, Serialize, Deserialize)]
+struct Exchange {
+    request: HttpRequest,
+    response: HttpResponse,
+}
+
+/// FNV-1a，文件名要跨版本稳定，不能用 DefaultHasher
+fn fnv1a(s: &str) -> u64 {
+    s.bytes().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+fn exchange_path(dir: &std::path::Path, req: &HttpRequest) -> Result<PathBuf> {
+    Ok(dir.join(format!("{:016x}.json", fnv1a(&req.replay_key()?))))
+}
+
+/// 转发给内层传输，同时把请求与响应写入目录
+///
+/// 请求去掉凭证头，响应去掉 `Set-Cookie`，录制目录里不会留下 auth_token 或 ct0。
+pub struct RecordTransport {
+    inner: Arc<dyn Transport>,
+    dir: PathBuf,
+}
+
+impl RecordTransport {
+    pub fn new(inner: Arc<dyn Transport>, dir: PathBuf) -> Self {
+        Self { inner, dir }
+    }
+}
+
+#[async_trait]
+impl Transport for RecordTransport {
+    async fn send(&self, req: HttpRequest) -> Result<HttpResponse> {
+        let path = exchange_path(&self.dir, &req)?;
+        let request = req.redacted();
+        let response = self.inner.send(req).await?;
+        let exchange = Exchange {
+            request,
+            response: response.redacted(),
+        };
+        tokio::fs::create_dir_all(&self.dir).await?;
+        tokio::fs::write(&path, serde_json::to_vec_pretty(&exchange)?)
+            .await
+            .with_context(|| format!("写入录制文件 {} 失败", path.display()))?;
+        Ok(response)
+    }
+}
+
+/// 只从录制目录读取响应
+pub struct ReplayTransport {
+    dir: PathBuf,
+}
+
+impl ReplayTransport {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    async fn send(&self, req: HttpRequest) -> Result<HttpResponse> {
+        let path = exchange_path(&self.dir, &req)?;
+        let content = tokio::fs::read(&path)
+            .await
+            .map_err(|_| anyhow!("没有录制过的响应: {} {}", req.method, req.url))?;
+        let exchange: Exchange = serde_json::from_slice(&content)?;
+        Ok(exchange.response)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type)]
+pub enum TransportMode {
+    Live,
+    Record(String),
+    Replay(String),
+}
+
+impl TransportMode {
+    /// 解析 `record:<dir>` / `replay:<dir>`，其他值视为直连
+    pub fn parse(s: &str) -> Self {
+        match s.split_once(':') {
+            Some(("record", dir)) => TransportMode::Record(dir.to_string()),
+            Some(("replay", dir)) => TransportMode::Replay(dir.to_string()),
+            _ => TransportMode::Live,
+        }
+    }
+
+    fn build(&self) -> Arc<dyn Transport> {
+        match self {
+            TransportMode::Live => Arc::new(ReqwestTransport::new()),
+            TransportMode::Record(dir) => Arc::new(RecordTransport::new(
+                Arc::new(ReqwestTransport::new()),
+                PathBuf::from(dir),
+            )),
+            TransportMode::Replay(dir) => Arc::new(ReplayTransport::new(PathBuf::from(dir))),
+        }
+    }
+}
+
+struct Current {
+    mode: TransportMode,
+    transport: Arc<dyn Transport>,
+}
+
+static CURRENT: LazyLock<RwLock<Current>> = LazyLock::new(|| {
+    let mode = std::env::var(TRANSPORT_ENV)
+        .map(|s| TransportMode::parse(&s))
+        .unwrap_or(TransportMode::Live);
+    RwLock::new(Current {
+        transport: mode.build(),
+        mode,
+    })
+});
+
+/// 当前使用的传输
+pub fn transport() -> Arc<dyn Transport> {
+    CURRENT.read().unwrap().transport.clone()
+}
+
+pub fn set_transport_mode(mode: TransportMode) {
+    let mut guard = CURRENT.write().unwrap();
+    guard.transport = mode.build();
+    guard.mode = mode;
+}
+
+/// 测试中换上自定义的传输，用 `set_transport_mode` 恢复
+#[cfg(test)]
+pub(crate) fn set_transport(transport: Arc<dyn Transport>) {
+    CURRENT.write().unwrap().transport = transport;
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn get_transport_mode() -> Result<TransportMode, String> {
+    Ok(CURRENT.read().unwrap().mode.clone())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn switch_transport_mode(mode: TransportMode) -> Result<(), String> {
+    tracing::info!("切换 X 接口传输模式: {:?}", mode);
+    set_transport_mode(mode);
+    Ok(())
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    struct Fixed;
+
+    #[async_trait]
+    impl Transport for Fixed {
+        async fn send(&self, _req: HttpRequest) -> Result<HttpResponse> {
+            Ok(HttpResponse {
+                status: 200,
+                headers: vec![
+                    ("x-rate-limit-remaining".to_string(), "7".to_string()),
+                    ("set-cookie".to_string(), "ct0=rotated; Path=/".to_string()),
+                ],
+                body: r#"{"data":{}}"#.to_string(),
+            })
+        }
+    }
+
+    fn request(query: &str) -> HttpRequest {
+        let mut headers = HeaderMap::new();
+        headers.insert("authorization", HeaderValue::from_static("Bearer secret"));
+        HttpRequest::new(
+            Method::GET,
+            Url::parse(&format!("https://x.com/i/api/graphql/id/Likes?{query}")).unwrap(),
+            &headers,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_record_then_replay() {
+        let dir = std::env::temp_dir().join(format!("x-transport-{}", std::process::id()));
+        let recorder = RecordTransport::new(Arc::new(Fixed), dir.clone());
+        recorder
+            .send(request("variables=%7B%22a%22%3A1%2C%22b%22%3A2%7D"))
+            .await
+            .unwrap();
+
+        // JSON 参数的 key 顺序不同也能命中
+        let replay = ReplayTransport::new(dir.clone());
+        let resp = replay
+            .send(request("variables=%7B%22b%22%3A2%2C%22a%22%3A1%7D"))
+            .await
+            .unwrap();
+        assert_eq!(resp.status, 200);
+        assert_eq!(resp.header_map()["x-rate-limit-remaining"], "7");
+
+        let recorded = std::fs::read_to_string(
+            std::fs::read_dir(&dir)
+                .unwrap()
+                .next()
+                .unwrap()
+                .unwrap()
+                .path(),
+        )
+        .unwrap();
+        assert!(!recorded.contains("secret"));
+        assert!(!recorded.contains("rotated"));
+        assert!(replay.send(request("variables=%7B%7D")).await.is_err());
+        std::fs::remove_dir_all(dir).ok();
+    }
+    /// 一页 Likes 的最小响应：一条带头像的推文与上下游标
+    pub(crate) fn likes_page() -> Value {
+        serde_json::json!({
+            "data": { "user": { "result": { "timeline": { "timeline": { "instructions": [{
+                "type": "TimelineAddEntries",
+                "entries": [
+                    {
+                        "entryId": "tweet-1900000000000000001",
+                        "content": { "itemContent": { "tweet_results": { "result": {
+                            "__typename": "Tweet",
+                            "rest_id": "1900000000000000001",
+                            "core": { "user_results": { "result": { "legacy": {
+                                "screen_name": "replay_author",
+                                "name": "Replay Author",
+                                "profile_image_url_https": "https://pbs.twimg.com/profile_images/1/replay_normal.jpg"
+                            } } } },
+                            "legacy": {
+                                "created_at": "Wed Mar 05 08:00:00 +0000 2025",
+                                "full_text": "recorded like",
+                                "entities": {}
+                            }
+                        } } } }
+                    },
+                    {
+                        "entryId": "cursor-top-1",
+                        "content": { "value": "cursor-a" }
+                    },
+                    {
+                        "entryId": "cursor-bottom-1",
+                        "content": { "value": "cursor-b" }
+                    }
+                ]
+            }] } } } } }
+        })
+    }
+
+    /// 回放录制的 Likes 响应，走完 send_graphql、解析与入库
+    #[test]
+    fn test_replay_likes_page() {
+        use crate::database::Crud;
+        use crate::domain::models::twitter::entities::DbEntitie;
+        use crate::domain::models::twitter::like::{DbLikedPost, LikedPost};
+        use crate::domain::models::twitter::post::DbPost;
+        use crate::domain::platform::twitter::api::models::{CursoredData, TimelineItem};
+        use crate::domain::platform::twitter::api::requests::user;
+        use crate::domain::platform::twitter::api::timeline::send_graphql;
+        use crate::domain::platform::twitter::auth::models::credentials::AuthCredential;
+        use crate::domain::platform::{handle_entities, testing, TaskKind};
+        use tokio_util::sync::CancellationToken;
+
+        testing::run(async {
+            let dir = std::env::temp_dir().join(format!("x-replay-{}", std::process::id()));
+            std::fs::create_dir_all(&dir).unwrap();
+            let mut cred =
+                AuthCredential::user("ct0=c; twid=u%3D42; auth_token=a; kdt=k".to_string())
+                    .unwrap();
+
+            // 按 send_graphql 的方式构造请求，写入录制文件
+            let payload = user::likes("42".to_string(), None, None);
+            let mut url = Url::parse(&payload.url).unwrap();
+            url.set_query(Some(&payload.params.to_string()));
+            let req = HttpRequest::new(payload.method.clone(), url, &cred.headers());
+            let exchange = Exchange {
+                request: req.redacted(),
+                response: HttpResponse {
+                    status: 200,
+                    headers: vec![],
+                    body: likes_page().to_string(),
+                },
+            };
+            std::fs::write(
+                exchange_path(&dir, &req).unwrap(),
+                serde_json::to_vec(&exchange).unwrap(),
+            )
+            .unwrap();
+
+            set_transport_mode(TransportMode::Replay(dir.to_string_lossy().to_string()));
+            let sent = send_graphql(&mut cred, payload, &CancellationToken::new()).await;
+            set_transport_mode(TransportMode::Live);
+            let (json, _) = sent.unwrap();
+
+            let mut page = CursoredData::<LikedPost>::from_response(&json, None, vec![])
+                .await
+                .unwrap();
+            assert_eq!(page.list.len(), 1);
+            assert_eq!(page.next, "cursor-b");
+            assert!(!page.is_end);
+
+            let params = serde_json::json!({ "account": "42" });
+            let entities = page
+                .list
+                .iter_mut()
+                .map(|item| {
+                    item.bind_params(&params);
+                    item.clone().into_entities(TaskKind::AssetDownload)
+                })
+                .collect::<Vec<_>>();
+            let tasks = handle_entities(DbEntitie::merge_all(entities))
+                .await
+                .unwrap();
+
+            let post_id = DbPost::record_id(1900000000000000001_i64);
+            assert!(DbPost::select_record(post_id.clone()).await.is_ok());
+            let likes = DbLikedPost::select_pagin(Some(42), 10, None).await.unwrap();
+            assert!(likes.iter().any(|l| l.post == post_id));
+            // 头像作为下载任务入库
+            assert!(tasks.iter().any(|t| t.kind == TaskKind::AssetDownload));
+            std::fs::remove_dir_all(dir).ok();
+        });
+    }
+}
@@ -0,0 +1,194 @@
+use super::core::transport::{self, HttpRequest, HttpResponse};
+use super::requests::user;
+use crate::domain::platform::job::Job;
+use crate::domain::platform::scheduler::Scheduler;
+use crate::domain::platform::twitter::auth::models::credentials::AuthCredential;
+use crate::domain::platform::twitter::auth::{account, auth};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use specta::Type;
+use tauri_specta::Event;
+
+/// 凭证状态，由 401/403 响应中的错误码区分
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+pub enum AuthState {
+    Valid,
+    /// 登录已过期或被撤销，需要重新导入 cookie
+    Expired,
+    /// 账号被临时锁定，需要在网页上完成验证
+    Locked,
+    Suspended,
+}
+
+impl AuthState {
+    /// 不是凭证问题（比如受保护账号的 403）时返回 None
+    pub fn classify(status: u16, body: &Value) -> Option<(Self, String)> {
+        if !matches!(status, 401 | 403) {
+            return None;
+        }
+        let error = body.pointer("/errors/0");
+        let code = error.and_then(|e| e.get("code")).and_then(Value::as_i64);
+        let message = error
+            .and_then(|e| e.get("message"))
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let state = match (status, code) {
+            (_, Some(64)) => AuthState::Suspended,
+            (_, Some(326)) => AuthState::Locked,
+            // 32 无法认证、89 token 失效、353 ct0 与 header 不匹配
+            (401, _) | (_, Some(32 | 89 | 353)) => AuthState::Expired,
+            _ => return None,
+        };
+        Some((state, message))
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Type, Event)]
+pub struct AuthStateEvent {
+    /// 账号 id，cookie 中没有 twid 时为空
+    pub account: Option<String>,
+    pub state: AuthState,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Type)]
+pub struct CredentialStatus {
+    pub state: AuthState,
+    pub id: Option<String>,
+    pub screen_name: Option<String>,
+    pub message: Option<String>,
+}
+
+fn account_of(cred: &AuthCredential) -> Option<String> {
+    cred.cookie
+        .as_ref()
+        .and_then(|c| c.user_id())
+        .map(|id| id.to_string())
+}
+
+/// 通知前端凭证状态变化，失效时调度器随之搁置该账号的 Task 与 Job
+pub fn report_auth_state(cred: &AuthCredential, state: AuthState, message: String) -> Result<()> {
+    Scheduler::<Job>::get()?
+        .emit(AuthStateEvent {
+            account: account_of(cred),
+            state,
+            message,
+        })
+        .map_err(|e| anyhow!("emit AuthStateEvent 失败: {}", e))
+}
+
+/// 响应是凭证失效时上报并返回状态
+pub fn check_auth(cred: &AuthCredential, resp: &HttpResponse) -> Option<(AuthState, String)> {
+    let body = resp.json::<Value>().unwrap_or_default();
+    let (state, message) = AuthState::classify(resp.status, &body)?;
+    tracing::warn!("凭证失效: {:?} {}", state, message);
+    if let Err(e) = report_auth_state(cred, state, message.clone()) {
+        tracing::error!("{}", e);
+    }
+    Some((state, message))
+}
+
+/// X 会通过 Set-Cookie 轮换 ct0 等 cookie，合并进凭证并写回账号保存的 cookie
+pub async fn absorb_set_cookies(cred: &mut AuthCredential, resp: &HttpResponse) {
+    if !cred.apply_set_cookies(&resp.headers) {
+        return;
+    }
+    let Some(id) = cred.cookie.as_ref().and_then(|c| c.user_id()) else {
+        return;
+    };
+    if let Err(e) = account::persist_set_cookies(id, &resp.headers).await {
+        tracing::warn!("保存轮换的 cookie 失败: {}", e);
+    }
+}
+
+/// 请求 Viewer 确认凭证可用，返回登录的账号
+#[tauri::command]
+#[specta::specta]
+pub async fn verify_credentials(account: Option<String>) -> Result<CredentialStatus, String> {
+    let account = account
+        .map(|a| a.parse::<i64>())
+        .transpose()
+        .map_err(|e| e.to_string())?;
+    let mut cred = auth::credential_for(account)
+        .await
+        .map_err(|e| e.to_string())?;
+    if !cred.cookie.as_ref().is_some_and(|c| c.is_valid()) {
+        return Err("cookie 缺少 ct0、twid、auth_token 或 kdt".to_string());
+    }
+
+    let req = user::viewer();
+    let mut url = reqwest::Url::parse(&req.url).map_err(|e| e.to_string())?;
+    url.set_query(Some(&req.params.to_string()));
+    let resp = transport::transport()
+        .send(HttpRequest::new(req.method, url, &cred.headers()))
+        .await
+        .map_err(|e| e.to_string())?;
+    absorb_set_cookies(&mut cred, &resp).await;
+
+    if let Some((state, message)) = check_auth(&cred, &resp) {
+        return Ok(CredentialStatus {
+            state,
+            id: account_of(&cred),
+            screen_name: None,
+            message: Some(message),
+        });
+    }
+    if !resp.is_success() {
+        return Err(format!("HTTP {}", resp.status));
+    }
+
+    let body = resp.json::<Value>().map_err(|e| e.to_string())?;
+    let result = body
+        .pointer("/data/viewer/user_results/result")
+        .ok_or_else(|| "Viewer 响应中没有用户信息".to_string())?;
+    let screen_name = result
+        .pointer("/core/screen_name")
+        .or_else(|| result.pointer("/legacy/screen_name"))
+        .and_then(Value::as_str)
+        .map(|s| s.to_string());
+    let id = result
+        .get("rest_id")
+        .and_then(Value::as_str)
+        .map(|s| s.to_string())
+        .or_else(|| account_of(&cred));
+    report_auth_state(&cred, AuthState::Valid, String::new()).map_err(|e| e.to_string())?;
+    Ok(CredentialStatus {
+        state: AuthState::Valid,
+        id,
+        screen_name,
+        message: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_classify() {
+        let body = |code: i64| json!({ "errors": [{ "code": code, "message": "x" }] });
+        assert_eq!(
+            AuthState::classify(401, &body(32)).map(|s| s.0),
+            Some(AuthState::Expired)
+        );
+        assert_eq!(
+            AuthState::classify(403, &body(326)).map(|s| s.0),
+            Some(AuthState::Locked)
+        );
+        assert_eq!(
+            AuthState::classify(403, &body(64)).map(|s| s.0),
+            Some(AuthState::Suspended)
+        );
+        assert_eq!(
+            AuthState::classify(401, &Value::Null).map(|s| s.0),
+            Some(AuthState::Expired)
+        );
+        // 受保护账号等资源层面的 403 不算凭证失效
+        assert_eq!(AuthState::classify(403, &body(179)), None);
+        assert_eq!(AuthState::classify(500, &body(32)), None);
+    }
+}
@@ -1,4 +1,5 @@
 use super::super::core::param_builder::Flag;
+use super::super::core::registry;
 
 pub enum Features {
     RwebListsTimelineRedesignEnabled,
@@ -36,6 +37,9 @@ pub enum Features {
     ResponsiveWebGrokAnalysisButtonFromBackend,
     CreatorSubscriptionsQuoteTweetPreviewEnabled,
     ResponsiveWebGrokImageAnnotationEnabled,
+    GraphqlTimelineV2BookmarkTimeline,
+    Spaces2022H2Clipping,
+    Spaces2022H2SpacesCommunities,
 }
 
 impl Flag for Features {
@@ -122,6 +126,13 @@ impl Flag for Features {
             Features::ResponsiveWebGrokImageAnnotationEnabled => {
                 "responsive_web_grok_image_annotation_enabled"
             }
+            Features::GraphqlTimelineV2BookmarkTimeline => "graphql_timeline_v2_bookmark_timeline",
+            Features::Spaces2022H2Clipping => "spaces_2022_h2_clipping",
+            Features::Spaces2022H2SpacesCommunities => "spaces_2022_h2_spaces_communities",
         }
     }
+
+    fn overrides() -> Vec<(String, bool)> {
+        registry::feature_overrides()
+    }
 }
@@ -1,13 +1,24 @@
 use super::super::core::param_builder::Flag;
+use super::super::core::registry;
 
 pub enum FieldToggles {
     WithArticlePlainText,
+    WithArticleRichContentState,
+    WithGrokAnalyze,
+    WithDisallowedReplyControls,
 }
 
 impl Flag for FieldToggles {
     fn as_str(&self) -> &'static str {
         match self {
             FieldToggles::WithArticlePlainText => "withArticlePlainText",
+            FieldToggles::WithArticleRichContentState => "withArticleRichContentState",
+            FieldToggles::WithGrokAnalyze => "withGrokAnalyze",
+            FieldToggles::WithDisallowedReplyControls => "withDisallowedReplyControls",
         }
     }
+
+    fn overrides() -> Vec<(String, bool)> {
+        registry::field_toggle_overrides()
+    }
 }
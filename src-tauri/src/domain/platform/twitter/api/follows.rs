@@ -0,0 +1,252 @@
+use super::requests::user;
+use super::timeline::{create_timeline_job, requeue_job, send_graphql};
+use crate::database::Crud;
+use crate::domain::models::twitter::entities::DbEntitie;
+use crate::domain::models::twitter::follows::{
+    diff_snapshots, DbFollowSnapshot, FollowDiff, FollowKind, FollowSnapshot,
+};
+use crate::domain::models::twitter::users::{DbUser, User};
+use crate::domain::platform::job::{Job, Mission};
+use crate::domain::platform::retry::TaskError;
+use crate::domain::platform::scheduler::{self, Scheduler};
+use crate::domain::platform::twitter::auth::auth::{self, AuthGenerator};
+use crate::domain::platform::{handle_entities, HandleSignal, Schedulable, Task, TaskKind};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use tauri_specta::Event;
+use tokio_util::sync::CancellationToken;
+
+const INSTRUCTIONS: &str = "/data/user/result/timeline/timeline/instructions";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Type, Event)]
+pub struct SnapshotFollowsEvent {
+    pub user_id: String,
+    pub kind: FollowKind,
+    pub count: u32,
+    pub running: bool,
+}
+
+/// 一页关注列表
+struct FollowsPage {
+    users: Vec<(i64, User)>,
+    next: Option<String>,
+}
+
+fn parse_page(json: &Value) -> Result<FollowsPage> {
+    let entries = json
+        .pointer(INSTRUCTIONS)
+        .and_then(Value::as_array)
+        .context("can not find follows timeline instructions")?
+        .iter()
+        .filter_map(|ins| ins.get("entries").and_then(Value::as_array))
+        .flatten();
+    let mut page = FollowsPage {
+        users: Vec::new(),
+        next: None,
+    };
+    for entry in entries {
+        let entry_id = entry.get("entryId").and_then(Value::as_str).unwrap_or("");
+        if entry_id.starts_with("cursor-bottom-") {
+            page.next = entry
+                .pointer("/content/value")
+                .and_then(Value::as_str)
+                .map(|s| s.to_string());
+        } else if let Some(user) = entry
+            .pointer("/content/itemContent/user_results/result")
+            .and_then(User::from_result)
+        {
+            page.users.push(user);
+        }
+    }
+    Ok(page)
+}
+
+fn job_id(user_id: &str, kind: FollowKind) -> surrealdb::RecordId {
+    Job::record_id(format!(
+        "{}:{}:{}",
+        Mission::SnapshotFollows.as_str(),
+        kind.as_str(),
+        user_id
+    ))
+}
+
+/// 为某个用户的关注者或正在关注拍一张新快照
+#[tauri::command]
+#[specta::specta]
+pub async fn snapshot_follows(user_id: String, kind: FollowKind) -> Result<(), String> {
+    start_snapshot(user_id, kind)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn start_snapshot(user_id: String, kind: FollowKind) -> Result<()> {
+    let key = user_id.parse::<i64>()?;
+    let id = job_id(&user_id, kind);
+    // 上一次快照还没拍完时不另开一张，免得留下空快照
+    if let Ok(old) = Job::select_record(id.clone()).await {
+        if !matches!(
+            old.status,
+            scheduler::Status::DeadLetter | scheduler::Status::Cancelled
+        ) {
+            return Err(anyhow!("Cannot create duplicate tasks."));
+        }
+    }
+    // 没有扫描在写入，之前中断留下的残缺快照不会再补全
+    DbFollowSnapshot::delete_incomplete(key, kind).await?;
+    let snapshot = DbFollowSnapshot::new(key, kind).create().await?;
+    create_timeline_job(
+        id,
+        Mission::SnapshotFollows,
+        json!({
+            "cursor": null,
+            "user_id": user_id,
+            "kind": kind,
+            "snapshot_id": snapshot.id.key().to_string(),
+        }),
+        Vec::new(),
+    )
+    .await
+}
+
+/// 拉取一页成员写入快照，用户资料与头像走常规的入库与下载
+pub async fn process_follows_chunk(
+    job: Job,
+    cancel: CancellationToken,
+) -> Result<Option<HandleSignal>> {
+    job.update_status(scheduler::Status::Running, None).await?;
+    let params = Job::select_record(job.id.clone()).await?.params;
+    let user_id = params
+        .get("user_id")
+        .and_then(Value::as_str)
+        .context("follows job has no user_id")?
+        .to_string();
+    let kind: FollowKind = params
+        .get("kind")
+        .cloned()
+        .map(serde_json::from_value)
+        .context("follows job has no kind")??;
+    let snapshot = params
+        .get("snapshot_id")
+        .and_then(Value::as_str)
+        .and_then(|s| s.parse::<i64>().ok())
+        .map(DbFollowSnapshot::record_id)
+        .context("follows job has no snapshot_id")?;
+    let cursor = params
+        .get("cursor")
+        .and_then(Value::as_str)
+        .map(|s| s.to_string());
+    let count = params.get("count").and_then(Value::as_u64).unwrap_or(0) as u32;
+
+    let mut cred = auth::UserAuth.generate().await?;
+    let req = match kind {
+        FollowKind::Followers => user::followers(user_id.clone(), None, cursor),
+        FollowKind::Following => user::following(user_id.clone(), None, cursor),
+    };
+    let (json_data, rate_limit) = send_graphql(&mut cred, req, &cancel).await?;
+    let page = parse_page(&json_data).map_err(|e| TaskError::Parse(e.to_string()))?;
+
+    let mut entities = DbEntitie::default();
+    let mut pairs = Vec::with_capacity(page.users.len());
+    for (rest_id, user) in &page.users {
+        let db_user = user.clone().into_db();
+        pairs.push((*rest_id, db_user.id.clone()));
+        entities.users.push(db_user);
+        let asset = user.avatar.clone().into_db();
+        entities
+            .tasks
+            .push(asset.clone().into_task(TaskKind::AssetDownload));
+        entities.assets.push(asset);
+    }
+    let tasks = handle_entities(entities).await?;
+    DbUser::enrich_rest_ids(&pairs).await?;
+    let sched = Scheduler::<Task>::get()?;
+    for task in tasks
+        .into_iter()
+        .filter(|t| !matches!(t.status, scheduler::Status::Succeeded))
+    {
+        sched.enqueue(task);
+    }
+    let members = pairs
+        .iter()
+        .map(|(rest_id, _)| *rest_id)
+        .collect::<Vec<_>>();
+    DbFollowSnapshot::append_members(snapshot.clone(), &members).await?;
+
+    // 到底时接口仍会给出游标，以本页没有成员为准
+    let is_end = members.is_empty() || page.next.is_none();
+    let count = count + members.len() as u32;
+    let mut next_params = params.clone();
+    if let Some(obj) = next_params.as_object_mut() {
+        obj.insert("cursor".to_string(), json!(page.next));
+        obj.insert("count".to_string(), json!(count));
+        obj.insert("is_end".to_string(), json!(is_end));
+    }
+    job.update_params(next_params).await?;
+    if is_end {
+        DbFollowSnapshot::merge(snapshot, json!({ "complete": true })).await?;
+    } else if cancel.is_cancelled() {
+        // 本页进度已保存；取消时报告 Cancelled，不能让 on_success 把 job 记为成功
+        return Err(TaskError::Cancelled.into());
+    } else {
+        requeue_job(job, &rate_limit).await?;
+    }
+
+    Scheduler::<Job>::get()?
+        .emit(SnapshotFollowsEvent {
+            user_id,
+            kind,
+            count,
+            running: !is_end,
+        })
+        .map_err(|e| anyhow!("emit SnapshotFollowsEvent 失败: {}", e))?;
+    Ok(Some(HandleSignal {
+        value: json!({ "is_end": is_end }),
+    }))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn follow_snapshots(
+    user_id: String,
+    kind: FollowKind,
+) -> Result<Vec<FollowSnapshot>, String> {
+    let user_id = user_id.parse::<i64>().map_err(|e| e.to_string())?;
+    DbFollowSnapshot::select_by_user(user_id, kind)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(DbFollowSnapshot::into_domain)
+        .collect::<Result<Vec<_>>>()
+        .map_err(|e| e.to_string())
+}
+
+/// 对比两次快照，`from` 为较早的一次
+#[tauri::command]
+#[specta::specta]
+pub async fn diff_follow_snapshots(from: String, to: String) -> Result<FollowDiff, String> {
+    let load = |id: String| async move {
+        let id = id.parse::<i64>()?;
+        DbFollowSnapshot::select_record(DbFollowSnapshot::record_id(id)).await
+    };
+    let (from, to) = tokio::try_join!(load(from), load(to)).map_err(|e| e.to_string())?;
+    if from.user_id != to.user_id || from.kind != to.kind {
+        return Err("snapshots belong to different lists".to_string());
+    }
+    // 残缺快照里缺的成员会被误报为取关
+    if !from.complete || !to.complete {
+        return Err("snapshot is incomplete".to_string());
+    }
+    diff_snapshots(from, to).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_follow_snapshot(id: String) -> Result<(), String> {
+    let id = id.parse::<i64>().map_err(|e| e.to_string())?;
+    DbFollowSnapshot::delete_record(DbFollowSnapshot::record_id(id))
+        .await
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,160 @@
+use super::requests::list;
+use super::timeline::{create_timeline_job, process_timeline_chunk, send_graphql};
+use crate::database::Crud;
+use crate::domain::models::twitter::entities::DbEntitie;
+use crate::domain::models::twitter::list::{DbListPost, DbListSource, ListedPost};
+use crate::domain::models::twitter::users::User;
+use crate::domain::platform::job::{Job, Mission};
+use crate::domain::platform::scheduler::{self, Scheduler};
+use crate::domain::platform::twitter::auth::auth::{self, AuthGenerator};
+use crate::domain::platform::twitter::auth::models::credentials::AuthCredential;
+use crate::domain::platform::{handle_entities, HandleSignal, Task, TaskKind};
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use tauri_specta::Event;
+use tokio_util::sync::CancellationToken;
+
+/// 成员列表最多翻的页数，超大列表只记录前面的成员
+const MAX_MEMBER_PAGES: usize = 10;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Type, Event)]
+pub struct ScanListEvent {
+    pub list_id: String,
+    pub count: u32,
+    pub running: bool,
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn scan_list_timeline(list_id: String) -> Result<(), String> {
+    let key = list_id.parse::<i64>().map_err(|e| e.to_string())?;
+    let intersection = DbListPost::select_pagin(key, 200, None)
+        .await
+        .map_err(|e| e.to_string())?;
+    let ids = intersection.into_iter().map(|p| p.post).collect::<Vec<_>>();
+    let mission = Mission::ScanList;
+    create_timeline_job(
+        Job::record_id(format!("{}:{}", mission.as_str(), list_id)),
+        mission,
+        json!({ "cursor": null, "list_id": list_id }),
+        ids,
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+/// 更新列表名称与成员，成员头像走常规的资源下载
+async fn refresh_list_source(
+    cred: &mut AuthCredential,
+    list_id: &str,
+    cancel: &CancellationToken,
+) -> Result<()> {
+    let (info, _) = send_graphql(cred, list::list_by_rest_id(list_id.to_string()), cancel).await?;
+    let name = info
+        .pointer("/data/list/name")
+        .and_then(Value::as_str)
+        .context("can not find list name")?
+        .to_string();
+    let description = info
+        .pointer("/data/list/description")
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+
+    let mut members = Vec::new();
+    let mut cursor = None;
+    for _ in 0..MAX_MEMBER_PAGES {
+        let req = list::list_members(list_id.to_string(), None, cursor.take());
+        let (json_data, rate_limit) = send_graphql(cred, req, cancel).await?;
+        let entries = json_data
+            .pointer("/data/list/members_timeline/timeline/instructions")
+            .and_then(Value::as_array)
+            .context("can not find members_timeline.instructions")?
+            .iter()
+            .filter_map(|ins| ins.get("entries").and_then(Value::as_array))
+            .flatten();
+        let mut page = Vec::new();
+        for entry in entries {
+            let entry_id = entry.get("entryId").and_then(Value::as_str).unwrap_or("");
+            if entry_id.starts_with("cursor-bottom-") {
+                cursor = entry
+                    .pointer("/content/value")
+                    .and_then(Value::as_str)
+                    .map(|s| s.to_string());
+            } else if let Some(user) = entry
+                .pointer("/content/itemContent/user_results/result/legacy")
+                .and_then(User::from_api)
+            {
+                page.push(user);
+            }
+        }
+        let empty = page.is_empty();
+        members.extend(page);
+        if empty || cursor.is_none() || rate_limit.is_exhausted() {
+            break;
+        }
+    }
+
+    let mut entities = DbEntitie::default();
+    for user in &members {
+        entities.users.push(user.clone().into_db());
+        let asset = user.avatar.clone().into_db();
+        entities
+            .tasks
+            .push(asset.clone().into_task(TaskKind::AssetDownload));
+        entities.assets.push(asset);
+    }
+    let tasks = handle_entities(entities).await?;
+    let sched = Scheduler::<Task>::get()?;
+    for task in tasks
+        .into_iter()
+        .filter(|t| !matches!(t.status, scheduler::Status::Succeeded))
+    {
+        sched.enqueue(task);
+    }
+
+    let source = DbListSource::new(list_id.parse::<i64>()?, name, description, members);
+    source.upsert().await?;
+    Ok(())
+}
+
+pub async fn process_list_chunk(
+    job: Job,
+    cancel: CancellationToken,
+) -> Result<Option<HandleSignal>> {
+    let list_id = job
+        .params
+        .get("list_id")
+        .and_then(Value::as_str)
+        .context("list job has no list_id")?
+        .to_string();
+
+    // 每次扫描的第一页顺带刷新列表信息，失败不影响推文归档
+    let params = Job::select_record(job.id.clone()).await?.params;
+    if matches!(params.get("cursor"), None | Some(Value::Null)) {
+        let mut cred = auth::UserAuth.generate().await?;
+        if let Err(e) = refresh_list_source(&mut cred, &list_id, &cancel).await {
+            tracing::warn!("刷新列表 {} 信息失败: {}", list_id, e);
+        }
+    }
+
+    let target = list_id.clone();
+    let progress = process_timeline_chunk::<ListedPost, _>(job, cancel, move |_, cursor| {
+        Ok(list::list_latest_tweets(target, None, cursor))
+    })
+    .await?;
+
+    Scheduler::<Job>::get()?
+        .emit(ScanListEvent {
+            list_id,
+            count: progress.count,
+            running: !progress.is_end,
+        })
+        .map_err(|e| anyhow!("emit ScanListEvent 失败: {}", e))?;
+    Ok(Some(HandleSignal {
+        value: json!({ "is_end": progress.is_end }),
+    }))
+}
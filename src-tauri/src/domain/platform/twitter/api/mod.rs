@@ -1,5 +1,15 @@
+pub mod bookmark;
+pub mod conversation;
 pub mod core;
+pub mod credentials;
 pub mod enums;
+pub mod follows;
+pub mod list;
 pub mod models;
+pub mod recheck;
 pub mod requests;
+pub mod search;
+pub mod space;
+pub mod timeline;
 pub mod user;
+pub mod user_timeline;
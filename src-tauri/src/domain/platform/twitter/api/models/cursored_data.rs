@@ -2,14 +2,189 @@ use std::collections::HashSet;
 
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{json, Value};
 use specta::Type;
 use surrealdb::RecordId;
 
 use crate::database::Crud;
+use crate::domain::models::twitter::bookmark::BookmarkedPost;
+use crate::domain::models::twitter::entities::DbEntitie;
 use crate::domain::models::twitter::like::LikedPost;
-use crate::domain::models::twitter::post::DbPost;
+use crate::domain::models::twitter::list::ListedPost;
+use crate::domain::models::twitter::post::{DbPost, Post};
+use crate::domain::models::twitter::search::SearchedPost;
 use crate::domain::platform::twitter::api::models::enums::EntriesType;
+use crate::domain::platform::TaskKind;
+
+/// 各类时间线 instructions 所在的位置
+const INSTRUCTION_PATHS: &[&str] = &[
+    "/data/user/result/timeline/timeline/instructions",
+    "/data/user/result/timeline/instructions",
+    "/data/bookmark_timeline_v2/timeline/instructions",
+    "/data/list/tweets_timeline/timeline/instructions",
+    "/data/search_by_raw_query/search_timeline/timeline/instructions",
+];
+
+/// 时间线里的一条推文记录，likes / bookmarks / 用户与列表时间线 / 搜索共用游标、end_band 与 seen streak 逻辑
+pub trait TimelineItem: Sized + Clone + Send + Sync + 'static {
+    fn from_entry(entry: &Value) -> Option<Self>;
+    fn rest_id(&self) -> i64;
+    fn sortidx(&self) -> u32;
+    fn set_sortidx(&mut self, sortidx: u32);
+    fn into_entities(self, task_kind: TaskKind) -> DbEntitie;
+    /// 用 job 参数补全响应里没有的归属信息，比如列表 id、所属账号
+    fn bind_params(&mut self, _params: &Value) {}
+}
+
+impl TimelineItem for LikedPost {
+    fn from_entry(entry: &Value) -> Option<Self> {
+        LikedPost::from_api(entry)
+    }
+    fn rest_id(&self) -> i64 {
+        self.post.rest_id
+    }
+    fn sortidx(&self) -> u32 {
+        self.sortidx
+    }
+    fn set_sortidx(&mut self, sortidx: u32) {
+        self.sortidx = sortidx;
+    }
+    fn into_entities(self, task_kind: TaskKind) -> DbEntitie {
+        LikedPost::into_entities(self, task_kind)
+    }
+    fn bind_params(&mut self, params: &Value) {
+        self.account = params
+            .get("account")
+            .and_then(Value::as_str)
+            .map(|s| s.to_string());
+    }
+}
+
+impl TimelineItem for BookmarkedPost {
+    fn from_entry(entry: &Value) -> Option<Self> {
+        BookmarkedPost::from_api(entry)
+    }
+    fn rest_id(&self) -> i64 {
+        self.post.rest_id
+    }
+    fn sortidx(&self) -> u32 {
+        self.sortidx
+    }
+    fn set_sortidx(&mut self, sortidx: u32) {
+        self.sortidx = sortidx;
+    }
+    fn into_entities(self, task_kind: TaskKind) -> DbEntitie {
+        BookmarkedPost::into_entities(self, task_kind)
+    }
+    fn bind_params(&mut self, params: &Value) {
+        self.account = params
+            .get("account")
+            .and_then(Value::as_str)
+            .map(|s| s.to_string());
+    }
+}
+
+impl TimelineItem for ListedPost {
+    fn from_entry(entry: &Value) -> Option<Self> {
+        let post = entry
+            .pointer("/content/itemContent/tweet_results/result")
+            .and_then(Post::from_api)?;
+        Some(Self {
+            list_id: 0,
+            sortidx: 0,
+            post,
+        })
+    }
+    fn rest_id(&self) -> i64 {
+        self.post.rest_id
+    }
+    fn sortidx(&self) -> u32 {
+        self.sortidx
+    }
+    fn set_sortidx(&mut self, sortidx: u32) {
+        self.sortidx = sortidx;
+    }
+    fn into_entities(self, task_kind: TaskKind) -> DbEntitie {
+        ListedPost::into_entities(self, task_kind)
+    }
+    fn bind_params(&mut self, params: &Value) {
+        if let Some(list_id) = params
+            .get("list_id")
+            .and_then(Value::as_str)
+            .and_then(|s| s.parse::<i64>().ok())
+        {
+            self.list_id = list_id;
+        }
+    }
+}
+
+impl TimelineItem for SearchedPost {
+    fn from_entry(entry: &Value) -> Option<Self> {
+        let post = entry
+            .pointer("/content/itemContent/tweet_results/result")
+            .and_then(Post::from_api)?;
+        Some(Self {
+            search_id: 0,
+            sortidx: 0,
+            post,
+        })
+    }
+    fn rest_id(&self) -> i64 {
+        self.post.rest_id
+    }
+    fn sortidx(&self) -> u32 {
+        self.sortidx
+    }
+    fn set_sortidx(&mut self, sortidx: u32) {
+        self.sortidx = sortidx;
+    }
+    fn into_entities(self, task_kind: TaskKind) -> DbEntitie {
+        SearchedPost::into_entities(self, task_kind)
+    }
+    fn bind_params(&mut self, params: &Value) {
+        if let Some(search_id) = params
+            .get("search_id")
+            .and_then(Value::as_str)
+            .and_then(|s| s.parse::<i64>().ok())
+        {
+            self.search_id = search_id;
+        }
+    }
+}
+
+/// 用户主页时间线直接存为帖子，不单独维护排序
+impl TimelineItem for Post {
+    fn from_entry(entry: &Value) -> Option<Self> {
+        entry
+            .pointer("/content/itemContent/tweet_results/result")
+            .and_then(Post::from_api)
+    }
+    fn rest_id(&self) -> i64 {
+        self.rest_id
+    }
+    fn sortidx(&self) -> u32 {
+        0
+    }
+    fn set_sortidx(&mut self, _sortidx: u32) {}
+    fn into_entities(self, task_kind: TaskKind) -> DbEntitie {
+        DbEntitie::from_post(self, task_kind)
+    }
+}
+
+/// module 条目（回复会话、媒体网格）内含多条推文，展开成普通条目的形状
+fn expand_module(entry: &Value) -> Vec<Value> {
+    match entry.pointer("/content/items").and_then(Value::as_array) {
+        Some(items) => items.iter().map(module_item_to_entry).collect(),
+        None => vec![entry.clone()],
+    }
+}
+
+fn module_item_to_entry(item: &Value) -> Value {
+    json!({
+        "entryId": item.get("entryId").cloned().unwrap_or(Value::Null),
+        "content": item.get("item").cloned().unwrap_or(Value::Null),
+    })
+}
 
 /// 游标数据结构，用于分页
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
@@ -19,7 +194,7 @@ pub struct CursoredData<T> {
     pub is_end: bool,
 }
 
-impl CursoredData<LikedPost> {
+impl<T: TimelineItem> CursoredData<T> {
     pub async fn from_response(
         response: &Value,
         sort_ref: Option<u32>,
@@ -29,27 +204,55 @@ impl CursoredData<LikedPost> {
         let mut seen_streak = 0; // 连续 seen 的个数
         let streak_threshold = 5;
 
-        let instruction = response
-            .pointer("/data/user/result/timeline/timeline/instructions/0")
-            .or_else(|| response.pointer("/data/user/result/timeline/instructions/0"))
-            .context("can not find timeline.instructions[0]")?;
-        instruction
-            .get("type")
-            .and_then(|v| v.as_str())
-            .and_then(|type_str| serde_json::from_value(Value::String(type_str.to_string())).ok())
-            .map(|e: EntriesType| e == EntriesType::TimelineAddEntries)
-            .context("timeline.instructions[0].type is not TimelineAddEntries")?;
-        let entries = instruction
-            .get("entries")
+        let instructions = INSTRUCTION_PATHS
+            .iter()
+            .find_map(|path| response.pointer(path))
             .and_then(Value::as_array)
-            .context("cannot find timeline.instructions[0].entries")?;
+            .context("can not find timeline.instructions")?;
+        // 部分时间线前面还有 TimelineClearCache / TimelinePinEntry 等指令；
+        // UserMedia 翻页时新条目通过 TimelineAddToModule 追加到已有的 module 中
+        let mut found = false;
+        let mut entries = Vec::new();
+        for ins in instructions {
+            let kind = ins
+                .get("type")
+                .and_then(|v| v.as_str())
+                .and_then(|type_str| {
+                    serde_json::from_value::<EntriesType>(Value::String(type_str.to_string())).ok()
+                });
+            match kind {
+                Some(EntriesType::TimelineAddEntries) => {
+                    found = true;
+                    let list = ins
+                        .get("entries")
+                        .and_then(Value::as_array)
+                        .context("cannot find TimelineAddEntries.entries")?;
+                    entries.extend(list.iter().flat_map(expand_module));
+                }
+                Some(EntriesType::TimelineAddToModule) => {
+                    found = true;
+                    if let Some(items) = ins.get("moduleItems").and_then(Value::as_array) {
+                        entries.extend(items.iter().map(module_item_to_entry));
+                    }
+                }
+                // 搜索翻页后游标改用替换指令下发
+                Some(EntriesType::TimelineReplaceEntry) => {
+                    if let Some(entry) = ins.get("entry") {
+                        entries.push(entry.clone());
+                    }
+                }
+                None => {}
+            }
+        }
+        if !found {
+            anyhow::bail!("timeline.instructions has no TimelineAddEntries");
+        }
         let mut list = Vec::with_capacity(entries.len());
         let mut next = String::new();
         let mut is_end = false;
-        if entries.len() == 0 {
-            is_end = true;
-        }
-        for entry in entries {
+        // 只剩游标、没有任何推文时说明已经翻到底
+        let mut parsed = 0;
+        for entry in &entries {
             // 找 cursor-bottom
             if let Some(entry_id) = entry.pointer("/entryId").and_then(Value::as_str) {
                 if entry_id.contains("cursor-bottom") {
@@ -61,9 +264,10 @@ impl CursoredData<LikedPost> {
                     continue;
                 }
             }
-            // 解析出一个 LikedPost
-            if let Some(liked) = LikedPost::from_api(&entry) {
-                let key = DbPost::record_id(liked.post.rest_id);
+            // 解析出一条推文
+            if let Some(item) = T::from_entry(entry) {
+                parsed += 1;
+                let key = DbPost::record_id(item.rest_id());
                 if seen.contains(&key) {
                     seen_streak += 1;
                     if seen_streak >= streak_threshold {
@@ -74,21 +278,27 @@ impl CursoredData<LikedPost> {
                     continue; // 跳过本条
                 }
                 seen_streak = 0;
-                list.push(liked);
+                list.push(item);
             }
         }
 
+        if parsed == 0 {
+            is_end = true;
+        }
+
         let base_ts = match sort_ref {
             Some(v) => v as i64,
             None => chrono::Utc::now().timestamp_millis(),
         };
-        for (i, post) in list.iter_mut().enumerate() {
-            post.sortidx = (base_ts - (i as i64)) as u32;
+        for (i, item) in list.iter_mut().enumerate() {
+            item.set_sortidx((base_ts - (i as i64)) as u32);
         }
 
         Ok(Self { list, next, is_end })
     }
+}
 
+impl<T: Serialize> CursoredData<T> {
     /// 将自身转换为JSON
     pub fn to_json(&self) -> Value {
         serde_json::to_value(self).unwrap_or(Value::Null)
@@ -26,4 +26,6 @@ pub enum BaseType {
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Type)]
 pub enum EntriesType {
     TimelineAddEntries,
+    TimelineAddToModule,
+    TimelineReplaceEntry,
 }
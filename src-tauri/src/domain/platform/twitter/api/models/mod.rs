@@ -1,4 +1,4 @@
 pub mod cursored_data;
 pub mod enums;
 
-pub use cursored_data::CursoredData;
+pub use cursored_data::{CursoredData, TimelineItem};
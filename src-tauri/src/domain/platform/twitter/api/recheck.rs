@@ -0,0 +1,128 @@
+use super::requests::tweet;
+use super::timeline::{create_timeline_job, requeue_job, send_graphql};
+use crate::database::Crud;
+use crate::domain::models::twitter::post::DbPost;
+use crate::domain::models::twitter::upstream::{DbPostCheck, UpstreamStatus};
+use crate::domain::platform::job::{Job, Mission};
+use crate::domain::platform::retry::TaskError;
+use crate::domain::platform::scheduler::{self, Scheduler};
+use crate::domain::platform::twitter::auth::auth::{self, AuthGenerator};
+use crate::domain::platform::{HandleSignal, Schedulable};
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use tauri_specta::Event;
+use tokio_util::sync::CancellationToken;
+
+/// 每次 job 回查的帖子数，每条一个请求
+const BATCH: i64 = 20;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Type, Event)]
+pub struct RecheckPostsEvent {
+    pub count: u32,
+    /// 上游已不可见的条数
+    pub gone: u32,
+    pub running: bool,
+}
+
+/// 从最新的帖子开始，逐条回查归档的主帖在 X 上是否还在
+#[tauri::command]
+#[specta::specta]
+pub async fn recheck_posts() -> Result<(), String> {
+    let mission = Mission::RecheckPosts;
+    create_timeline_job(
+        Job::record_id(mission.as_str()),
+        mission,
+        json!({ "cursor": null, "count": 0, "gone": 0 }),
+        Vec::new(),
+    )
+    .await
+    .map_err(|e| e.to_string())
+}
+
+pub async fn process_recheck_chunk(
+    job: Job,
+    cancel: CancellationToken,
+) -> Result<Option<HandleSignal>> {
+    job.update_status(scheduler::Status::Running, None).await?;
+    let params = Job::select_record(job.id.clone()).await?.params;
+    let cursor = params
+        .get("cursor")
+        .and_then(Value::as_str)
+        .and_then(|s| s.parse::<i64>().ok());
+    let mut count = params.get("count").and_then(Value::as_u64).unwrap_or(0) as u32;
+    let mut gone = params.get("gone").and_then(Value::as_u64).unwrap_or(0) as u32;
+
+    let ids = DbPost::select_root_ids_before(cursor, BATCH).await?;
+    let mut cred = auth::UserAuth.generate().await?;
+    let mut checks = Vec::with_capacity(ids.len());
+    let mut last = cursor;
+    let mut outcome = Ok(None);
+    let mut stopped = false;
+    for id in &ids {
+        let Ok(rest_id) = id.key().to_string().parse::<i64>() else {
+            continue;
+        };
+        let req = tweet::tweet_result_by_rest_id(rest_id.to_string());
+        match send_graphql(&mut cred, req, &cancel).await {
+            Ok((json_data, rate_limit)) => {
+                let (status, reason) = UpstreamStatus::classify(
+                    json_data
+                        .pointer("/data/tweetResult")
+                        .unwrap_or(&Value::Null),
+                );
+                if status != UpstreamStatus::Available {
+                    gone += 1;
+                }
+                checks.push(DbPostCheck::new(rest_id, status, reason));
+                last = Some(rest_id);
+                count += 1;
+                if rate_limit.is_exhausted() {
+                    outcome = Ok(Some(rate_limit));
+                    stopped = true;
+                    break;
+                }
+            }
+            Err(e) => {
+                // 已回查的先保存，重试时从失败的那条继续
+                outcome = Err(e);
+                stopped = true;
+                break;
+            }
+        }
+    }
+
+    if !checks.is_empty() {
+        DbPostCheck::insert_replace(checks).await?;
+    }
+    let is_end = !stopped && (ids.len() as i64) < BATCH;
+    let mut next_params = params.clone();
+    if let Some(obj) = next_params.as_object_mut() {
+        obj.insert("cursor".to_string(), json!(last.map(|l| l.to_string())));
+        obj.insert("count".to_string(), json!(count));
+        obj.insert("gone".to_string(), json!(gone));
+        obj.insert("is_end".to_string(), json!(is_end));
+    }
+    job.update_params(next_params).await?;
+    let rate_limit = outcome?;
+
+    if !is_end {
+        // 本页进度已保存；取消时报告 Cancelled，不能让 on_success 把 job 记为成功
+        if cancel.is_cancelled() {
+            return Err(TaskError::Cancelled.into());
+        }
+        requeue_job(job, &rate_limit.unwrap_or_default()).await?;
+    }
+    Scheduler::<Job>::get()?
+        .emit(RecheckPostsEvent {
+            count,
+            gone,
+            running: !is_end,
+        })
+        .map_err(|e| anyhow!("emit RecheckPostsEvent 失败: {}", e))?;
+    Ok(Some(HandleSignal {
+        value: json!({ "is_end": is_end }),
+    }))
+}
@@ -0,0 +1,51 @@
+use super::super::core::param_builder::{Builder, ParamBuilder};
+use super::super::core::registry::Operation;
+use super::super::enums::variables::Variables;
+use super::model::{Params, Payload};
+use super::user::timeline_features;
+
+use reqwest::Method;
+
+fn list_payload(op: Operation, variable_builder: ParamBuilder<Variables>) -> Payload {
+    let params = Params {
+        features: timeline_features().build(),
+        variables: variable_builder.build(),
+        field_toggles: None,
+    };
+
+    Payload {
+        method: Method::GET,
+        url: op.url(),
+        params,
+    }
+}
+
+/// 列表按时间倒序的推文
+pub fn list_latest_tweets(list_id: String, count: Option<u32>, cursor: Option<String>) -> Payload {
+    let mut variable_builder = ParamBuilder::<Variables>::new();
+    variable_builder
+        .set("listId", list_id)
+        .set("count", count.unwrap_or(100));
+    if let Some(cursor_value) = cursor {
+        variable_builder.set("cursor", cursor_value);
+    }
+    list_payload(Operation::ListLatestTweetsTimeline, variable_builder)
+}
+
+/// 列表名称、简介等基本信息
+pub fn list_by_rest_id(list_id: String) -> Payload {
+    let mut variable_builder = ParamBuilder::<Variables>::new();
+    variable_builder.set("listId", list_id);
+    list_payload(Operation::ListByRestId, variable_builder)
+}
+
+pub fn list_members(list_id: String, count: Option<u32>, cursor: Option<String>) -> Payload {
+    let mut variable_builder = ParamBuilder::<Variables>::new();
+    variable_builder
+        .set("listId", list_id)
+        .set("count", count.unwrap_or(100));
+    if let Some(cursor_value) = cursor {
+        variable_builder.set("cursor", cursor_value);
+    }
+    list_payload(Operation::ListMembers, variable_builder)
+}
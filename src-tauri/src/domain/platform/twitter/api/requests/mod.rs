@@ -1,2 +1,6 @@
+pub mod list;
 pub mod model;
+pub mod search;
+pub mod space;
+pub mod tweet;
 pub mod user;
@@ -1,4 +1,5 @@
 use reqwest::Method;
+use serde_json::{Map, Value};
 
 pub struct Payload {
     pub method: Method,
@@ -13,6 +14,15 @@ pub struct Params {
 }
 
 impl Params {
+    /// 补上接口要求的 feature，默认关闭
+    pub fn add_features(&mut self, names: &[String]) {
+        let mut map: Map<String, Value> = serde_json::from_str(&self.features).unwrap_or_default();
+        for name in names {
+            map.entry(name.clone()).or_insert(Value::Bool(false));
+        }
+        self.features = Value::Object(map).to_string();
+    }
+
     pub fn to_string(&self) -> String {
         // 使用URL编码处理参数值
         let encoded_features = urlencoding::encode(&self.features);
@@ -0,0 +1,37 @@
+use super::super::core::param_builder::{Builder, ParamBuilder};
+use super::super::core::registry::Operation;
+use super::super::enums::variables::Variables;
+use super::model::{Params, Payload};
+use super::user::timeline_features;
+
+use reqwest::Method;
+
+/// 按关键词搜索推文，`product` 取 Top / Latest / Media
+pub fn search_timeline(
+    raw_query: String,
+    product: &str,
+    count: Option<u32>,
+    cursor: Option<String>,
+) -> Payload {
+    let mut variable_builder = ParamBuilder::<Variables>::new();
+    variable_builder
+        .set("rawQuery", raw_query)
+        .set("count", count.unwrap_or(20))
+        .set("querySource", "typed_query")
+        .set("product", product);
+    if let Some(cursor_value) = cursor {
+        variable_builder.set("cursor", cursor_value);
+    }
+
+    let params = Params {
+        features: timeline_features().build(),
+        variables: variable_builder.build(),
+        field_toggles: None,
+    };
+
+    Payload {
+        method: Method::GET,
+        url: Operation::SearchTimeline.url(),
+        params,
+    }
+}
@@ -0,0 +1,37 @@
+use super::super::core::param_builder::{Builder, ParamBuilder};
+use super::super::core::registry::Operation;
+use super::super::enums::feature::Features;
+use super::super::enums::variables::Variables;
+use super::model::{Params, Payload};
+use super::user::timeline_features;
+use crate::keys;
+
+use reqwest::Method;
+
+/// 语音空间的标题、主持人、起止时间与人数
+pub fn audio_space_by_id(id: String) -> Payload {
+    let mut feature_builder = timeline_features();
+    let mut variable_builder = ParamBuilder::<Variables>::new();
+
+    feature_builder.enable(keys![
+        Features::Spaces2022H2Clipping,
+        Features::Spaces2022H2SpacesCommunities,
+    ]);
+    variable_builder
+        .set("id", id)
+        .set("isMetatagsQuery", false)
+        .set("withReplays", true)
+        .set("withListeners", true);
+
+    let params = Params {
+        features: feature_builder.build(),
+        variables: variable_builder.build(),
+        field_toggles: None,
+    };
+
+    Payload {
+        method: Method::GET,
+        url: Operation::AudioSpaceById.url(),
+        params,
+    }
+}
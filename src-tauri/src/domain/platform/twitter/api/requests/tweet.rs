@@ -0,0 +1,83 @@
+use super::super::core::param_builder::{Builder, ParamBuilder};
+use super::super::core::registry::Operation;
+use super::super::enums::field_toggles::FieldToggles;
+use super::super::enums::variables::Variables;
+use super::model::{Params, Payload};
+use super::user::timeline_features;
+use crate::keys;
+
+use reqwest::Method;
+
+/// 推文详情及其下的回复会话，翻页时带上 bottom cursor
+pub fn tweet_detail(focal_id: String, cursor: Option<String>) -> Payload {
+    let feature_builder = timeline_features();
+    let mut variable_builder = ParamBuilder::<Variables>::new();
+    let mut field_toggles_builder = ParamBuilder::<FieldToggles>::new();
+
+    variable_builder
+        .set("focalTweetId", focal_id)
+        .set("rankingMode", "Relevance")
+        .enable(keys![
+            Variables::WithCommunity,
+            Variables::WithQuickPromoteEligibilityTweetFields,
+            Variables::WithBirdwatchNotes,
+            Variables::WithVoice,
+        ])
+        .disable(keys![
+            Variables::WithRuxInjections,
+            Variables::IncludePromotedContent,
+        ]);
+
+    if let Some(cursor_value) = cursor {
+        variable_builder
+            .set("cursor", cursor_value)
+            .set("referrer", "tweet");
+    }
+
+    field_toggles_builder
+        .enable(keys![
+            FieldToggles::WithArticleRichContentState,
+            FieldToggles::WithDisallowedReplyControls,
+        ])
+        .disable(keys![
+            FieldToggles::WithArticlePlainText,
+            FieldToggles::WithGrokAnalyze,
+        ]);
+
+    let params = Params {
+        features: feature_builder.build(),
+        variables: variable_builder.build(),
+        field_toggles: Some(field_toggles_builder.build()),
+    };
+
+    Payload {
+        method: Method::GET,
+        url: Operation::TweetDetail.url(),
+        params,
+    }
+}
+
+/// 单条推文，用来回查归档的帖子是否还在
+pub fn tweet_result_by_rest_id(id: String) -> Payload {
+    let mut variable_builder = ParamBuilder::<Variables>::new();
+    let mut field_toggles_builder = ParamBuilder::<FieldToggles>::new();
+
+    variable_builder.set("tweetId", id).disable(keys![
+        Variables::WithCommunity,
+        Variables::IncludePromotedContent,
+        Variables::WithVoice,
+    ]);
+    field_toggles_builder.disable(keys![FieldToggles::WithArticlePlainText]);
+
+    let params = Params {
+        features: timeline_features().build(),
+        variables: variable_builder.build(),
+        field_toggles: Some(field_toggles_builder.build()),
+    };
+
+    Payload {
+        method: Method::GET,
+        url: Operation::TweetResultByRestId.url(),
+        params,
+    }
+}
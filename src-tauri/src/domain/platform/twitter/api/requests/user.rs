@@ -1,4 +1,5 @@
 use super::super::core::param_builder::{Builder, ParamBuilder};
+use super::super::core::registry::Operation;
 use super::super::enums::feature::Features;
 use super::super::enums::field_toggles::FieldToggles;
 use super::super::enums::variables::Variables;
@@ -7,11 +8,9 @@ use crate::keys;
 
 use reqwest::Method;
 
-pub fn likes(id: String, count: Option<u32>, cursor: Option<String>) -> Payload {
+/// 推文时间线类接口共用的 features
+pub(super) fn timeline_features() -> ParamBuilder<Features> {
     let mut feature_builder = ParamBuilder::<Features>::new();
-    let mut variable_builder = ParamBuilder::<Variables>::new();
-    let mut field_toggles_builder = ParamBuilder::<FieldToggles>::new();
-
     feature_builder
         .enable(keys![
             Features::ProfileLabelImprovementsPcfLabelInPostEnabled,
@@ -48,6 +47,32 @@ pub fn likes(id: String, count: Option<u32>, cursor: Option<String>) -> Payload
             Features::CreatorSubscriptionsQuoteTweetPreviewEnabled,
             Features::ResponsiveWebEnhanceCardsEnabled,
         ]);
+    feature_builder
+}
+
+/// 当前登录的账号，用来检查凭证是否仍然有效
+pub fn viewer() -> Payload {
+    let feature_builder = timeline_features();
+    let mut variable_builder = ParamBuilder::<Variables>::new();
+    variable_builder.set("withCommunitiesMemberships", true);
+
+    let params = Params {
+        features: feature_builder.build(),
+        variables: variable_builder.build(),
+        field_toggles: None,
+    };
+
+    Payload {
+        method: Method::GET,
+        url: Operation::Viewer.url(),
+        params,
+    }
+}
+
+pub fn likes(id: String, count: Option<u32>, cursor: Option<String>) -> Payload {
+    let feature_builder = timeline_features();
+    let mut variable_builder = ParamBuilder::<Variables>::new();
+    let mut field_toggles_builder = ParamBuilder::<FieldToggles>::new();
 
     // 设置变量
     variable_builder
@@ -76,7 +101,128 @@ pub fn likes(id: String, count: Option<u32>, cursor: Option<String>) -> Payload
 
     Payload {
         method: Method::GET,
-        url: "https://x.com/i/api/graphql/eQl7iWsCr2fChppuJdAeRw/Likes".to_string(),
+        url: Operation::Likes.url(),
+        params,
+    }
+}
+
+pub fn bookmarks(count: Option<u32>, cursor: Option<String>) -> Payload {
+    let mut feature_builder = timeline_features();
+    let mut variable_builder = ParamBuilder::<Variables>::new();
+
+    feature_builder.enable(Features::GraphqlTimelineV2BookmarkTimeline);
+
+    variable_builder
+        .set("count", count.unwrap_or(100))
+        .disable(Variables::IncludePromotedContent);
+
+    if let Some(cursor_value) = cursor {
+        variable_builder.set("cursor", cursor_value);
+    }
+
+    let params = Params {
+        features: feature_builder.build(),
+        variables: variable_builder.build(),
+        field_toggles: None,
+    };
+
+    Payload {
+        method: Method::GET,
+        url: Operation::Bookmarks.url(),
+        params,
+    }
+}
+
+/// 用户主页时间线共用的变量，各接口只在个别开关上有差异
+fn user_timeline_variables(
+    id: String,
+    count: Option<u32>,
+    cursor: Option<String>,
+) -> ParamBuilder<Variables> {
+    let mut variable_builder = ParamBuilder::<Variables>::new();
+    variable_builder
+        .set("userId", id)
+        .set("count", count.unwrap_or(100))
+        .enable(keys![Variables::WithVoice, Variables::WithV2timeline])
+        .disable(Variables::IncludePromotedContent);
+    if let Some(cursor_value) = cursor {
+        variable_builder.set("cursor", cursor_value);
+    }
+    variable_builder
+}
+
+fn user_timeline_payload(op: Operation, variable_builder: ParamBuilder<Variables>) -> Payload {
+    let mut field_toggles_builder = ParamBuilder::<FieldToggles>::new();
+    field_toggles_builder.disable(keys![FieldToggles::WithArticlePlainText]);
+
+    let params = Params {
+        features: timeline_features().build(),
+        variables: variable_builder.build(),
+        field_toggles: Some(field_toggles_builder.build()),
+    };
+
+    Payload {
+        method: Method::GET,
+        url: op.url(),
+        params,
+    }
+}
+
+pub fn user_tweets(id: String, count: Option<u32>, cursor: Option<String>) -> Payload {
+    let mut variable_builder = user_timeline_variables(id, count, cursor);
+    variable_builder.enable(Variables::WithQuickPromoteEligibilityTweetFields);
+    user_timeline_payload(Operation::UserTweets, variable_builder)
+}
+
+pub fn user_tweets_and_replies(id: String, count: Option<u32>, cursor: Option<String>) -> Payload {
+    let mut variable_builder = user_timeline_variables(id, count, cursor);
+    variable_builder.enable(Variables::WithCommunity);
+    user_timeline_payload(Operation::UserTweetsAndReplies, variable_builder)
+}
+
+pub fn user_media(id: String, count: Option<u32>, cursor: Option<String>) -> Payload {
+    let mut variable_builder = user_timeline_variables(id, count, cursor);
+    variable_builder.disable(keys![
+        Variables::WithClientEventToken,
+        Variables::WithBirdwatchNotes,
+    ]);
+    user_timeline_payload(Operation::UserMedia, variable_builder)
+}
+
+fn follows_payload(
+    op: Operation,
+    id: String,
+    count: Option<u32>,
+    cursor: Option<String>,
+) -> Payload {
+    let mut variable_builder = ParamBuilder::<Variables>::new();
+    variable_builder
+        .set("userId", id)
+        .set("count", count.unwrap_or(100))
+        .disable(Variables::IncludePromotedContent);
+    if let Some(cursor_value) = cursor {
+        variable_builder.set("cursor", cursor_value);
+    }
+
+    let params = Params {
+        features: timeline_features().build(),
+        variables: variable_builder.build(),
+        field_toggles: None,
+    };
+
+    Payload {
+        method: Method::GET,
+        url: op.url(),
         params,
     }
 }
+
+/// 关注该用户的账号
+pub fn followers(id: String, count: Option<u32>, cursor: Option<String>) -> Payload {
+    follows_payload(Operation::Followers, id, count, cursor)
+}
+
+/// 该用户关注的账号
+pub fn following(id: String, count: Option<u32>, cursor: Option<String>) -> Payload {
+    follows_payload(Operation::Following, id, count, cursor)
+}
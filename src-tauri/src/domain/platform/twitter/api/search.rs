@@ -0,0 +1,197 @@
+use std::time::Duration;
+
+use super::requests::search;
+use super::timeline::{create_timeline_job, process_timeline_chunk};
+use crate::database::Crud;
+use crate::domain::models::twitter::search::{
+    DbSavedSearch, DbSearchPost, SavedSearch, SearchProduct, SearchedPost,
+};
+use crate::domain::platform::job::{Job, Mission};
+use crate::domain::platform::scheduler::{Scheduler, Status};
+use crate::domain::platform::HandleSignal;
+use crate::utils::serialize::i64_to_string;
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use surrealdb::RecordId;
+use tauri_specta::Event;
+use tokio_util::sync::CancellationToken;
+
+/// 检查定时搜索是否到期的间隔
+const TICK: Duration = Duration::from_secs(60);
+
+#[derive(Serialize, Deserialize, Debug, Clone, Type, Event)]
+pub struct ScanSearchEvent {
+    #[serde(serialize_with = "i64_to_string")]
+    #[specta(type = String)]
+    pub search_id: i64,
+    pub count: u32,
+    pub running: bool,
+}
+
+fn job_id(search_id: i64) -> RecordId {
+    Job::record_id(format!("{}:{}", Mission::ScanSearch.as_str(), search_id))
+}
+
+/// 为保存的搜索发起一次扫描，已命中的推文作为 end_band，遇到连续已见过的即停止
+async fn run_search(search: DbSavedSearch) -> Result<()> {
+    let saved = search.clone().into_domain()?;
+    let seen = DbSearchPost::select_pagin(saved.id, 200, None).await?;
+    let ids = seen.into_iter().map(|p| p.post).collect::<Vec<_>>();
+    create_timeline_job(
+        job_id(saved.id),
+        Mission::ScanSearch,
+        json!({ "cursor": null, "search_id": saved.id.to_string() }),
+        ids,
+    )
+    .await?;
+    DbSavedSearch::merge(search.id, json!({ "last_run_at": Utc::now() })).await?;
+    Ok(())
+}
+
+/// 定时运行到期的保存搜索，上一次扫描尚未结束的跳过
+pub fn spawn_search_schedule() {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(TICK);
+        loop {
+            interval.tick().await;
+            let searches = match DbSavedSearch::select_all().await {
+                Ok(searches) => searches,
+                Err(e) => {
+                    tracing::error!("读取保存的搜索失败: {}", e);
+                    continue;
+                }
+            };
+            let now = Utc::now();
+            for search in searches.into_iter().filter(|s| s.is_due(now)) {
+                let Ok(search_id) = search.id.key().to_string().parse::<i64>() else {
+                    continue;
+                };
+                // 只跳过仍在进行中的扫描，已结束或失败的由 create_timeline_job 替换
+                if let Ok(job) = Job::select_record(job_id(search_id)).await {
+                    if matches!(
+                        job.status,
+                        Status::Pending | Status::Running | Status::Failed
+                    ) {
+                        continue;
+                    }
+                }
+                if let Err(e) = run_search(search).await {
+                    tracing::error!("定时搜索 {} 运行失败: {}", search_id, e);
+                }
+            }
+        }
+    });
+}
+
+pub async fn process_search_chunk(
+    job: Job,
+    cancel: CancellationToken,
+) -> Result<Option<HandleSignal>> {
+    let search_id = job
+        .params
+        .get("search_id")
+        .and_then(Value::as_str)
+        .and_then(|s| s.parse::<i64>().ok())
+        .context("search job has no search_id")?;
+    let search = DbSavedSearch::select_record(DbSavedSearch::record_id(search_id))
+        .await
+        .context("saved search was deleted")?;
+
+    let progress = process_timeline_chunk::<SearchedPost, _>(job, cancel, move |_, cursor| {
+        Ok(search::search_timeline(
+            search.query,
+            search.product.as_str(),
+            None,
+            cursor,
+        ))
+    })
+    .await?;
+
+    Scheduler::<Job>::get()?
+        .emit(ScanSearchEvent {
+            search_id,
+            count: progress.count,
+            running: !progress.is_end,
+        })
+        .map_err(|e| anyhow!("emit ScanSearchEvent 失败: {}", e))?;
+    Ok(Some(HandleSignal {
+        value: json!({ "is_end": progress.is_end }),
+    }))
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn create_saved_search(
+    query: String,
+    product: SearchProduct,
+    interval_mins: Option<u32>,
+) -> Result<SavedSearch, String> {
+    let query = query.trim().to_string();
+    if query.is_empty() {
+        return Err("search query is empty".to_string());
+    }
+    DbSavedSearch::new(query, product, interval_mins)
+        .create()
+        .await
+        .and_then(DbSavedSearch::into_domain)
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn update_saved_search(
+    id: String,
+    interval_mins: Option<u32>,
+) -> Result<SavedSearch, String> {
+    let id = id.parse::<i64>().map_err(|e| e.to_string())?;
+    DbSavedSearch::merge(
+        DbSavedSearch::record_id(id),
+        json!({ "interval_mins": interval_mins }),
+    )
+    .await
+    .and_then(DbSavedSearch::into_domain)
+    .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn delete_saved_search(id: String) -> Result<(), String> {
+    let id = id.parse::<i64>().map_err(|e| e.to_string())?;
+    // 先停掉这个搜索的扫描，免得下一页找不到搜索而反复重试
+    Scheduler::<Job>::get()
+        .map_err(|e| e.to_string())?
+        .cancel(job_id(id));
+    Job::delete_record(job_id(id)).await.ok();
+    DbSearchPost::delete_by_search(id)
+        .await
+        .map_err(|e| e.to_string())?;
+    DbSavedSearch::delete_record(DbSavedSearch::record_id(id))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn all_saved_searches() -> Result<Vec<SavedSearch>, String> {
+    DbSavedSearch::select_all()
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(DbSavedSearch::into_domain)
+        .collect::<Result<Vec<_>>>()
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn run_saved_search(id: String) -> Result<(), String> {
+    let id = id.parse::<i64>().map_err(|e| e.to_string())?;
+    let search = DbSavedSearch::select_record(DbSavedSearch::record_id(id))
+        .await
+        .map_err(|e| e.to_string())?;
+    run_search(search).await.map_err(|e| e.to_string())
+}
@@ -0,0 +1,51 @@
+use super::requests::space;
+use super::timeline::send_graphql;
+use crate::database::Crud;
+use crate::domain::models::twitter::entities::DbEntitie;
+use crate::domain::models::twitter::space::DbSpace;
+use crate::domain::platform::scheduler::{self, Scheduler};
+use crate::domain::platform::twitter::auth::auth::{self, AuthGenerator};
+use crate::domain::platform::{handle_entities, HandleSignal, Schedulable, Task, TaskKind};
+
+use anyhow::Result;
+use tokio_util::sync::CancellationToken;
+
+/// 拉取语音空间信息写入缓存，主持人头像交给下载任务
+pub async fn fetch_space(task: Task, cancel: CancellationToken) -> Result<Option<HandleSignal>> {
+    task.update_status(scheduler::Status::Running, None).await?;
+    let space_id = task.tar.key().to_string();
+    if let Ok(cached) = DbSpace::select_record(task.tar.clone()).await {
+        if cached.is_final() {
+            return Ok(None);
+        }
+    }
+
+    let mut cred = auth::UserAuth.generate().await?;
+    let (json_data, _) = send_graphql(
+        &mut cred,
+        space::audio_space_by_id(space_id.clone()),
+        &cancel,
+    )
+    .await?;
+    let (space, host) = DbSpace::from_api(&space_id, &json_data);
+
+    if let Some(host) = host {
+        let mut entities = DbEntitie::default();
+        let asset = host.avatar.clone().into_db();
+        entities
+            .tasks
+            .push(asset.clone().into_task(TaskKind::AssetDownload));
+        entities.assets.push(asset);
+        entities.users.push(host.into_db());
+        let tasks = handle_entities(entities).await?;
+        let sched = Scheduler::<Task>::get()?;
+        for task in tasks
+            .into_iter()
+            .filter(|t| !matches!(t.status, scheduler::Status::Succeeded))
+        {
+            sched.enqueue(task);
+        }
+    }
+    space.upsert().await?;
+    Ok(None)
+}
@@ -0,0 +1,314 @@
+use super::core::rate_limit::RateLimit;
+use super::core::registry;
+use super::core::transport::{self, HttpRequest};
+use super::credentials::{absorb_set_cookies, check_auth};
+use super::models::{CursoredData, TimelineItem};
+use super::requests::model::Payload;
+use crate::database::Crud;
+use crate::domain::models::twitter::entities::DbEntitie;
+use crate::domain::platform::job::{Job, Mission};
+use crate::domain::platform::retry::TaskError;
+use crate::domain::platform::scheduler::Scheduler;
+use crate::domain::platform::twitter::auth::auth;
+use crate::domain::platform::twitter::auth::models::credentials::AuthCredential;
+use crate::domain::platform::{handle_entities, scheduler, Schedulable, Task, TaskKind};
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Value};
+use surrealdb::RecordId;
+use tokio_util::sync::CancellationToken;
+
+/// 一页时间线处理完后的进度
+pub struct TimelineProgress {
+    /// 本次扫描累计新增的条数
+    pub count: u32,
+    pub is_end: bool,
+}
+
+/// 从 cookie 中取出当前登录用户的数字 id
+pub fn twid(cred: &AuthCredential) -> Result<String> {
+    Ok(cred
+        .cookie
+        .clone()
+        .ok_or(anyhow!("No cookie found"))?
+        .twid
+        .ok_or(anyhow!("No twid found in cookie"))?
+        .replace("u%3D", ""))
+}
+
+/// job 参数中记录的账号 id，早先创建的 job 没有该参数，使用默认账号
+pub fn job_account(params: &Value) -> Option<i64> {
+    params
+        .get("account")
+        .and_then(Value::as_str)
+        .and_then(|s| s.parse::<i64>().ok())
+}
+
+/// 发送一个 GraphQL 请求，统一处理取消、限流、凭证失效与非 2xx 状态
+///
+/// 接口报告缺少 feature 时记入 registry，补上后重试一次；
+/// 响应中轮换的 cookie 会同步到 `cred` 与库中，同一个 job 的后续请求即用新的 CSRF 头。
+pub async fn send_graphql(
+    cred: &mut AuthCredential,
+    mut req: Payload,
+    cancel: &CancellationToken,
+) -> Result<(Value, RateLimit)> {
+    let transport = transport::transport();
+    let mut retried = false;
+    loop {
+        let mut url = reqwest::Url::parse(&req.url)?;
+        url.set_query(Some(&req.params.to_string()));
+
+        let request = HttpRequest::new(req.method.clone(), url.clone(), &cred.headers());
+        let resp = tokio::select! {
+            _ = cancel.cancelled() => return Err(TaskError::Cancelled.into()),
+            resp = transport.send(request) => resp?,
+        };
+
+        absorb_set_cookies(cred, &resp).await;
+        let rate_limit = RateLimit::from_headers(&resp.header_map());
+        if resp.status == 429 {
+            return Err(TaskError::RateLimited {
+                reset_at: rate_limit.reset_at(),
+            }
+            .into());
+        }
+        if let Some((_, reason)) = check_auth(cred, &resp) {
+            return Err(TaskError::Unauthorized {
+                status: resp.status,
+                reason,
+                account: cred.cookie.as_ref().and_then(|c| c.user_id()),
+            }
+            .into());
+        }
+        if resp.status == 400 && !retried {
+            let missing = registry::missing_features(&resp.json::<Value>().unwrap_or_default());
+            if !missing.is_empty() {
+                tracing::warn!("接口要求新的 features: {:?}，补上后重试", missing);
+                registry::learn_features(&missing).await?;
+                req.params.add_features(&missing);
+                retried = true;
+                continue;
+            }
+        }
+        if !resp.is_success() {
+            return Err(TaskError::Http {
+                status: resp.status,
+                url: url.to_string(),
+            }
+            .into());
+        }
+
+        return Ok((resp.json::<Value>()?, rate_limit));
+    }
+}
+
+/// 新建一个分页扫描 job 并入队
+///
+/// `end_band` 是库中最新的一批帖子，扫描遇到连续已存在的帖子即视为追上了上次的进度。
+pub async fn create_timeline_job(
+    id: RecordId,
+    mission: Mission,
+    params: Value,
+    end_band: Vec<RecordId>,
+) -> Result<()> {
+    // 上一次扫描已结束、进入死信或被取消，允许重新发起
+    if let Ok(old) = Job::select_record(id.clone()).await {
+        if matches!(
+            old.status,
+            scheduler::Status::Succeeded
+                | scheduler::Status::DeadLetter
+                | scheduler::Status::Cancelled
+        ) {
+            old.delete().await?;
+        }
+    }
+    let retry_policy = mission.retry_policy();
+    let job = Job {
+        id,
+        mission,
+        status: scheduler::Status::Pending,
+        params,
+        end_band,
+        error: None,
+        retry_count: 0,
+        max_retry_count: retry_policy.max_attempts,
+        retry_policy,
+        next_attempt_at: None,
+        started_at: None,
+        finished_at: None,
+    };
+
+    job.create()
+        .await
+        .context("Cannot create duplicate tasks.")?;
+    Scheduler::<Job>::get()?.enqueue(job);
+    Ok(())
+}
+
+/// 本页成功后登记下一页，handle 返回后由调度器接着派发
+///
+/// 下一页重新计算重试次数；额度用完则等到窗口重置。
+pub async fn requeue_job(mut job: Job, rate_limit: &RateLimit) -> Result<()> {
+    let next_at = rate_limit.is_exhausted().then(|| rate_limit.reset_at());
+    if let Some(until) = next_at {
+        Scheduler::<Job>::get()?.limit_until(until);
+    }
+    job.set_retry(0, next_at);
+    Job::merge(
+        job.id.clone(),
+        json!({ "retry_count": 0, "next_attempt_at": next_at }),
+    )
+    .await?;
+    Scheduler::<Job>::get()?.continue_after(job);
+    Ok(())
+}
+
+/// 拉取并入库一页时间线，未到底时登记下一页继续
+///
+/// `build` 根据凭证与游标构造本页的请求，其余游标、end_band、限流处理各类时间线共用。
+pub async fn process_timeline_chunk<T, F>(
+    job: Job,
+    cancel: CancellationToken,
+    build: F,
+) -> Result<TimelineProgress>
+where
+    T: TimelineItem,
+    F: FnOnce(&AuthCredential, Option<String>) -> Result<Payload>,
+{
+    let job_record = Job::select_record(job.id.clone()).await?;
+    job.update_status(scheduler::Status::Running, None).await?;
+    let cursor = job_record
+        .params
+        .get("cursor")
+        .and_then(Value::as_str)
+        .map(|s| s.to_string());
+    let last_sortidx = job_record
+        .params
+        .get("last_sortidx")
+        .and_then(Value::as_u64)
+        .map(|v| v as u32);
+    let count = (job_record
+        .params
+        .get("count")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0)) as u32;
+    let mut cred = auth::credential_for(job_account(&job_record.params)).await?;
+    let req = build(&cred, cursor)?;
+    let (json_data, rate_limit) = send_graphql(&mut cred, req, &cancel).await?;
+
+    let mut result =
+        CursoredData::<T>::from_response(&json_data, last_sortidx, job_record.end_band.clone())
+            .await
+            .map_err(|e| TaskError::Parse(e.to_string()))?;
+    for item in result.list.iter_mut() {
+        item.bind_params(&job_record.params);
+    }
+    let entities = result
+        .list
+        .iter()
+        .map(|item| item.clone().into_entities(TaskKind::AssetDownload))
+        .collect::<Vec<_>>();
+    let merged = DbEntitie::merge_all(entities);
+    let checked_tasks = handle_entities(merged).await?;
+    let excu_tasks = checked_tasks
+        .iter()
+        .filter(|t| !matches!(t.status, scheduler::Status::Succeeded))
+        .cloned()
+        .collect::<Vec<_>>();
+    for task in excu_tasks {
+        let _ = Scheduler::<Task>::get()?.enqueue(task);
+    }
+    let count = count + result.list.len() as u32;
+    // 保留 job 自带的参数（如目标用户），只覆盖分页进度
+    let mut params = job_record.params.clone();
+    let progress = json!({
+        "cursor": result.next,
+        "count": count,
+        "is_end": result.is_end,
+        "last_sortidx": result.list.last().map(|p| p.sortidx())
+    });
+    match params.as_object_mut() {
+        Some(obj) => obj.extend(progress.as_object().cloned().unwrap_or_default()),
+        None => params = progress,
+    }
+    job_record.update_params(params).await?;
+    if !result.is_end {
+        // 本页进度已保存；取消时报告 Cancelled，不能让 on_success 把 job 记为成功
+        if cancel.is_cancelled() {
+            return Err(TaskError::Cancelled.into());
+        }
+        requeue_job(job, &rate_limit).await?;
+    }
+
+    Ok(TimelineProgress {
+        count,
+        is_end: result.is_end,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::platform::pause::{self, PauseScope};
+    use crate::domain::platform::testing;
+    use crate::domain::platform::twitter::api::core::transport::tests::likes_page;
+    use crate::domain::platform::twitter::api::core::transport::{
+        set_transport, set_transport_mode, HttpResponse, Transport, TransportMode,
+    };
+    use crate::domain::platform::twitter::auth::account::add_account;
+    use async_trait::async_trait;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// 返回一页 Likes，同时模拟用户在响应到达前点了取消
+    struct CancelDuring(RecordId);
+
+    #[async_trait]
+    impl Transport for CancelDuring {
+        async fn send(&self, _req: HttpRequest) -> Result<HttpResponse> {
+            Scheduler::<Job>::get()?.cancel(self.0.clone());
+            Ok(HttpResponse {
+                status: 200,
+                headers: vec![],
+                body: likes_page().to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_cancel_during_page_marks_cancelled() {
+        testing::run(async {
+            add_account("ct0=c; twid=u%3D77; auth_token=a".to_string())
+                .await
+                .unwrap();
+            let id = Job::record_id(format!("{}:77", Mission::ScanLikes.as_str()));
+            set_transport(Arc::new(CancelDuring(id.clone())));
+            create_timeline_job(
+                id.clone(),
+                Mission::ScanLikes,
+                json!({ "cursor": null, "account": "77" }),
+                vec![],
+            )
+            .await
+            .unwrap();
+            pause::set_paused(PauseScope::Jobs, false).await.unwrap();
+
+            let mut status = None;
+            for _ in 0..50 {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                status = Job::select_record(id.clone()).await.ok().map(|j| j.status);
+                if matches!(status, Some(scheduler::Status::Cancelled)) {
+                    break;
+                }
+            }
+            pause::set_paused(PauseScope::Jobs, true).await.unwrap();
+            set_transport_mode(TransportMode::Live);
+
+            assert!(matches!(status, Some(scheduler::Status::Cancelled)));
+            // 取消前这一页的进度已经保存
+            let job = Job::select_record(id).await.unwrap();
+            assert_eq!(job.params["cursor"], "cursor-b");
+        });
+    }
+}
@@ -0,0 +1,146 @@
+use super::requests::user;
+use super::timeline::{create_timeline_job, process_timeline_chunk};
+use crate::database::Crud;
+use crate::domain::models::twitter::post::{DbPost, Post};
+use crate::domain::models::twitter::users::DbUser;
+use crate::domain::platform::job::{Job, Mission};
+use crate::domain::platform::scheduler::{self, Scheduler};
+use crate::domain::platform::HandleSignal;
+
+use anyhow::{anyhow, Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use specta::Type;
+use tauri_specta::Event;
+use tokio_util::sync::CancellationToken;
+
+/// 用户主页上的几种时间线
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+pub enum UserTimeline {
+    Tweets,
+    TweetsAndReplies,
+    Media,
+}
+
+impl UserTimeline {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            UserTimeline::Tweets => "tweets",
+            UserTimeline::TweetsAndReplies => "tweets_and_replies",
+            UserTimeline::Media => "media",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Type, Event)]
+pub struct ScanUserTimelineEvent {
+    pub user_id: String,
+    pub timeline: UserTimeline,
+    pub count: u32,
+    pub running: bool,
+}
+
+/// 归档某个用户（也可以是自己）的主页时间线
+///
+/// `user_id` 为数字 id；提供 `screen_name` 时以库中该作者最新的帖子作为 end_band，
+/// 只补抓新发的内容。同一用户同一时间线的 job 尚未结束时，从保存的游标处继续。
+#[tauri::command]
+#[specta::specta]
+pub async fn scan_user_timeline(
+    user_id: String,
+    screen_name: Option<String>,
+    timeline: UserTimeline,
+) -> Result<(), String> {
+    start_user_timeline(user_id, screen_name, timeline)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+async fn start_user_timeline(
+    user_id: String,
+    screen_name: Option<String>,
+    timeline: UserTimeline,
+) -> Result<()> {
+    let mission = Mission::ScanUserTimeline;
+    let id = Job::record_id(format!(
+        "{}:{}:{}",
+        mission.as_str(),
+        timeline.as_str(),
+        user_id
+    ));
+
+    if let Ok(mut old) = Job::select_record(id.clone()).await {
+        if old.status == scheduler::Status::Running {
+            return Ok(());
+        }
+        // 游标保留在 params 里，重置状态后直接接着翻页
+        Job::merge(
+            id,
+            json!({
+                "status": scheduler::Status::Pending,
+                "retry_count": 0,
+                "next_attempt_at": null,
+                "error": null,
+            }),
+        )
+        .await?;
+        old.status = scheduler::Status::Pending;
+        old.retry_count = 0;
+        old.next_attempt_at = None;
+        Scheduler::<Job>::get()?.enqueue(old);
+        return Ok(());
+    }
+
+    let end_band = match screen_name {
+        Some(name) => DbPost::select_ids_by_author(DbUser::record_id(name), 200).await?,
+        None => Vec::new(),
+    };
+    create_timeline_job(
+        id,
+        mission,
+        json!({ "cursor": null, "user_id": user_id, "timeline": timeline }),
+        end_band,
+    )
+    .await
+}
+
+pub async fn process_user_timeline_chunk(
+    job: Job,
+    cancel: CancellationToken,
+) -> Result<Option<HandleSignal>> {
+    let user_id = job
+        .params
+        .get("user_id")
+        .and_then(Value::as_str)
+        .context("user timeline job has no user_id")?
+        .to_string();
+    let timeline: UserTimeline = job
+        .params
+        .get("timeline")
+        .cloned()
+        .map(serde_json::from_value)
+        .context("user timeline job has no timeline")??;
+
+    let target = user_id.clone();
+    let progress =
+        process_timeline_chunk::<Post, _>(job, cancel, move |_, cursor| match timeline {
+            UserTimeline::Tweets => Ok(user::user_tweets(target, None, cursor)),
+            UserTimeline::TweetsAndReplies => {
+                Ok(user::user_tweets_and_replies(target, None, cursor))
+            }
+            UserTimeline::Media => Ok(user::user_media(target, None, cursor)),
+        })
+        .await?;
+
+    Scheduler::<Job>::get()?
+        .emit(ScanUserTimelineEvent {
+            user_id,
+            timeline,
+            count: progress.count,
+            running: !progress.is_end,
+        })
+        .map_err(|e| anyhow!("emit ScanUserTimelineEvent 失败: {}", e))?;
+    Ok(Some(HandleSignal {
+        value: json!({ "is_end": progress.is_end }),
+    }))
+}
@@ -0,0 +1,182 @@
+//! 多个 X 账号
+//!
+//! 每个账号的 cookie 各占一条 `DbUserKV`：默认账号沿用 `UserKey::Twitter`，
+//! 其余账号存为 `UserKey::TwitterAccount(id)`。账号 id 即 cookie 中 twid 的数字部分。
+
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::models::authcookie::AuthCookie;
+use crate::database::Crud;
+use crate::domain::models::twitter::bookmark::DbBookmark;
+use crate::domain::models::twitter::like::DbLikedPost;
+use crate::domain::models::userkv::{DbUserKV, UserKey};
+use crate::utils::serialize::{i64_from_string_or_number, i64_to_string};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Type)]
+pub struct XAccount {
+    #[serde(serialize_with = "i64_to_string")]
+    #[serde(deserialize_with = "i64_from_string_or_number")]
+    #[specta(type = String)]
+    pub id: i64,
+    pub is_default: bool,
+}
+
+async fn stored_cookie(key: UserKey) -> Result<Option<String>> {
+    Ok(DbUserKV::get(key)
+        .await
+        .map_err(|e| anyhow!("读取 cookie 失败: {}", e))?
+        .map(|v| v.into_string()))
+}
+
+async fn store_cookie(key: UserKey, cookie: String) -> Result<()> {
+    DbUserKV::new(key, cookie).upsert().await?;
+    Ok(())
+}
+
+async fn remove_cookie(key: UserKey) -> Result<()> {
+    DbUserKV::delete_record(DbUserKV::record_id(key.as_key())).await
+}
+
+/// 默认账号的 id，未登录时为空
+pub async fn default_account() -> Result<Option<i64>> {
+    Ok(stored_cookie(UserKey::Twitter)
+        .await?
+        .and_then(|raw| AuthCookie::parse(&raw).user_id()))
+}
+
+/// 账号 cookie 所在的 key
+pub async fn account_key(id: i64) -> Result<UserKey> {
+    if default_account().await? == Some(id) {
+        return Ok(UserKey::Twitter);
+    }
+    let key = UserKey::TwitterAccount(id);
+    match stored_cookie(key.clone()).await? {
+        Some(_) => Ok(key),
+        None => Err(anyhow!("账号 {} 不存在", id)),
+    }
+}
+
+/// 账号保存的原始 cookie
+pub async fn account_cookie(id: i64) -> Result<String> {
+    let key = account_key(id).await?;
+    stored_cookie(key)
+        .await?
+        .ok_or_else(|| anyhow!("账号 {} 不存在", id))
+}
+
+/// 把响应里轮换的 cookie 合并进账号保存的 cookie
+///
+/// 以库中的值为基础合并，不会用某个 job 手里较旧的副本覆盖其它 job 刚写入的轮换。
+pub async fn persist_set_cookies(id: i64, headers: &[(String, String)]) -> Result<()> {
+    let key = account_key(id).await?;
+    let Some(raw) = stored_cookie(key.clone()).await? else {
+        return Ok(());
+    };
+    let mut cookie = AuthCookie::parse(&raw);
+    if cookie.merge_set_cookies(headers) {
+        store_cookie(key, cookie.to_string()).await?;
+    }
+    Ok(())
+}
+
+/// 命令传入的账号 id，为空时取默认账号
+pub async fn resolve_account(account: Option<String>) -> Result<i64> {
+    match account {
+        Some(id) => Ok(id.parse::<i64>()?),
+        None => default_account()
+            .await?
+            .ok_or_else(|| anyhow!("No Twitter cookie found")),
+    }
+}
+
+pub async fn list_accounts() -> Result<Vec<XAccount>> {
+    let default = default_account().await?;
+    let mut accounts = default
+        .map(|id| XAccount {
+            id,
+            is_default: true,
+        })
+        .into_iter()
+        .collect::<Vec<_>>();
+    for kv in DbUserKV::select_all().await? {
+        if let Ok(UserKey::TwitterAccount(id)) = UserKey::from_str(&kv.id.key().to_string()) {
+            accounts.push(XAccount {
+                id,
+                is_default: false,
+            });
+        }
+    }
+    Ok(accounts)
+}
+
+/// 启动时把多账号之前的喜欢与书签归到默认账号名下，同时迁移旧的主键
+pub async fn init() -> Result<()> {
+    let account = default_account().await?;
+    DbLikedPost::claim_unattributed(account).await?;
+    DbBookmark::claim_unattributed(account).await?;
+    Ok(())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn all_accounts() -> Result<Vec<XAccount>, String> {
+    list_accounts().await.map_err(|e| e.to_string())
+}
+
+/// 添加账号或更新已有账号的 cookie；还没有默认账号时成为默认账号
+#[tauri::command]
+#[specta::specta]
+pub async fn add_account(cookie: String) -> Result<XAccount, String> {
+    let id = AuthCookie::parse(&cookie)
+        .user_id()
+        .ok_or_else(|| "cookie 中没有 twid".to_string())?;
+    let default = default_account().await.map_err(|e| e.to_string())?;
+    let key = match default {
+        None => UserKey::Twitter,
+        Some(d) if d == id => UserKey::Twitter,
+        Some(_) => UserKey::TwitterAccount(id),
+    };
+    let is_default = key == UserKey::Twitter;
+    store_cookie(key, cookie).await.map_err(|e| e.to_string())?;
+    Ok(XAccount { id, is_default })
+}
+
+/// 删除账号的凭证，已抓取的数据保留
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_account(id: String) -> Result<(), String> {
+    let id = id.parse::<i64>().map_err(|e| e.to_string())?;
+    let key = account_key(id).await.map_err(|e| e.to_string())?;
+    remove_cookie(key).await.map_err(|e| e.to_string())
+}
+
+/// 把账号设为默认，原默认账号转存为普通账号
+#[tauri::command]
+#[specta::specta]
+pub async fn set_default_account(id: String) -> Result<(), String> {
+    let id = id.parse::<i64>().map_err(|e| e.to_string())?;
+    if default_account().await.map_err(|e| e.to_string())? == Some(id) {
+        return Ok(());
+    }
+    let key = UserKey::TwitterAccount(id);
+    let cookie = stored_cookie(key.clone())
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("账号 {} 不存在", id))?;
+    if let Some(old) = stored_cookie(UserKey::Twitter)
+        .await
+        .map_err(|e| e.to_string())?
+    {
+        if let Some(old_id) = AuthCookie::parse(&old).user_id() {
+            store_cookie(UserKey::TwitterAccount(old_id), old)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    store_cookie(UserKey::Twitter, cookie)
+        .await
+        .map_err(|e| e.to_string())?;
+    remove_cookie(key).await.map_err(|e| e.to_string())
+}
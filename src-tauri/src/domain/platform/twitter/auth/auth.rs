@@ -1,10 +1,13 @@
+use super::account;
 use super::enums::authentication::EAuthentication;
 use super::enums::login::{ELoginUrls, Tokens};
 use super::models::credentials::AuthCredential;
 use crate::domain::models::userkv::{get_userkv_value, UserKey};
+use crate::domain::platform::twitter::api::core::transport::{self, HttpRequest};
 use anyhow::{Context, Result};
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Method, Url};
 
 #[async_trait]
 pub trait AuthGenerator: Send + Sync {
@@ -17,22 +20,27 @@ pub struct GuestAuth;
 #[async_trait]
 impl AuthGenerator for GuestAuth {
     async fn generate(&self) -> Result<AuthCredential> {
-        let client = Client::new();
-        let resp = client
-            .post(ELoginUrls::GuestToken.as_str())
-            .bearer_auth(Tokens::AuthToken.as_str())
-            .send()
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "authorization",
+            HeaderValue::from_str(&format!("Bearer {}", Tokens::AuthToken.as_str()))?,
+        );
+        let req = HttpRequest::new(
+            Method::POST,
+            Url::parse(ELoginUrls::GuestToken.as_str())?,
+            &headers,
+        );
+        let resp = transport::transport()
+            .send(req)
             .await
             .context("Failed to request guest token")?;
 
-        let status = resp.status();
-        if !status.is_success() {
-            anyhow::bail!("Failed to get guest token: HTTP {}", status);
+        if !resp.is_success() {
+            anyhow::bail!("Failed to get guest token: HTTP {}", resp.status);
         }
 
         let body: GuestTokenResponse = resp
             .json()
-            .await
             .context("Failed to parse guest token response")?;
 
         Ok(AuthCredential::guest(body.guest_token))
@@ -53,6 +61,25 @@ impl AuthGenerator for UserAuth {
     }
 }
 
+/// 指定 X 账号的用户认证
+pub struct AccountAuth(pub i64);
+
+#[async_trait]
+impl AuthGenerator for AccountAuth {
+    async fn generate(&self) -> Result<AuthCredential> {
+        let cookie = account::account_cookie(self.0).await?;
+        AuthCredential::user(cookie)
+    }
+}
+
+/// 取账号的凭证，未指定账号时使用默认账号
+pub async fn credential_for(account: Option<i64>) -> Result<AuthCredential> {
+    match account {
+        Some(id) => AccountAuth(id).generate().await,
+        None => UserAuth.generate().await,
+    }
+}
+
 /// 根据认证类型创建对应的 AuthGenerator
 pub fn auth_generator_for(auth_type: EAuthentication) -> Box<dyn AuthGenerator> {
     match auth_type {
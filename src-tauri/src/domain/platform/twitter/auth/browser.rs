@@ -0,0 +1,359 @@
+//! 从本机浏览器配置中直接读取 x.com 的 cookie（仅 Linux）
+//!
+//! Firefox 读 `cookies.sqlite`；Chromium 系只支持未加密的 cookie 库，
+//! 用系统密钥环加密的值会被跳过。浏览器运行时数据库被锁定，读之前先复制一份。
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::account::{add_account, XAccount};
+use super::models::authcookie::AuthCookie;
+
+const COOKIE_HOSTS: [&str; 2] = ["x.com", "twitter.com"];
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Type)]
+pub enum BrowserKind {
+    Firefox,
+    Chromium,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+pub struct BrowserProfile {
+    pub kind: BrowserKind,
+    /// 浏览器名与配置名，比如 `Google Chrome / Profile 1`
+    pub name: String,
+    /// cookie 数据库的路径
+    pub cookies_path: String,
+}
+
+fn home() -> Option<PathBuf> {
+    std::env::var_os("HOME").map(PathBuf::from)
+}
+
+/// Firefox 的配置目录，含 snap 与 flatpak 安装
+fn firefox_roots(home: &Path) -> Vec<PathBuf> {
+    vec![
+        home.join(".mozilla/firefox"),
+        home.join("snap/firefox/common/.mozilla/firefox"),
+        home.join(".var/app/org.mozilla.firefox/.mozilla/firefox"),
+    ]
+}
+
+/// Chromium 系浏览器的名称与配置目录
+fn chromium_roots(home: &Path) -> Vec<(&'static str, PathBuf)> {
+    let config = home.join(".config");
+    vec![
+        ("Chromium", config.join("chromium")),
+        ("Google Chrome", config.join("google-chrome")),
+        ("Brave", config.join("BraveSoftware/Brave-Browser")),
+        ("Microsoft Edge", config.join("microsoft-edge")),
+        ("Vivaldi", config.join("vivaldi")),
+    ]
+}
+
+/// 解析 profiles.ini，返回 (配置名, 配置目录)
+fn parse_profiles_ini(root: &Path, content: &str) -> Vec<(String, PathBuf)> {
+    let mut profiles = Vec::new();
+    let mut section: Option<(Option<String>, Option<String>, bool)> = None;
+    let mut flush = |section: Option<(Option<String>, Option<String>, bool)>| {
+        if let Some((name, Some(path), relative)) = section {
+            let dir = if relative {
+                root.join(&path)
+            } else {
+                PathBuf::from(&path)
+            };
+            profiles.push((name.unwrap_or(path), dir));
+        }
+    };
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            flush(section.take());
+            if line.starts_with("[Profile") {
+                section = Some((None, None, true));
+            }
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        if let Some((name, path, relative)) = section.as_mut() {
+            match key {
+                "Name" => *name = Some(value.to_string()),
+                "Path" => *path = Some(value.to_string()),
+                "IsRelative" => *relative = value == "1",
+                _ => {}
+            }
+        }
+    }
+    flush(section);
+    profiles
+}
+
+fn firefox_profiles(home: &Path) -> Vec<BrowserProfile> {
+    firefox_roots(home)
+        .into_iter()
+        .filter_map(|root| {
+            let content = std::fs::read_to_string(root.join("profiles.ini")).ok()?;
+            Some(parse_profiles_ini(&root, &content))
+        })
+        .flatten()
+        .map(|(name, dir)| (name, dir.join("cookies.sqlite")))
+        .filter(|(_, path)| path.is_file())
+        .map(|(name, path)| BrowserProfile {
+            kind: BrowserKind::Firefox,
+            name: format!("Firefox / {}", name),
+            cookies_path: path.to_string_lossy().to_string(),
+        })
+        .collect()
+}
+
+fn chromium_profiles(home: &Path) -> Vec<BrowserProfile> {
+    let mut profiles = Vec::new();
+    for (browser, root) in chromium_roots(home) {
+        let Ok(entries) = std::fs::read_dir(&root) else {
+            continue;
+        };
+        let mut dirs = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .collect::<Vec<_>>();
+        dirs.sort();
+        for dir in dirs {
+            // 新版本把 cookie 库移到了 Network 子目录
+            let path = [dir.join("Network/Cookies"), dir.join("Cookies")]
+                .into_iter()
+                .find(|p| p.is_file());
+            if let Some(path) = path {
+                let name = dir.file_name().unwrap_or_default().to_string_lossy();
+                profiles.push(BrowserProfile {
+                    kind: BrowserKind::Chromium,
+                    name: format!("{} / {}", browser, name),
+                    cookies_path: path.to_string_lossy().to_string(),
+                });
+            }
+        }
+    }
+    profiles
+}
+
+/// 复制数据库（连同 WAL）到临时目录后只读打开，避开浏览器持有的锁
+fn open_copy(path: &Path) -> Result<(Connection, PathBuf)> {
+    let dir = std::env::temp_dir().join(format!(
+        "crab-cookies-{}-{}",
+        std::process::id(),
+        chrono::Utc::now().timestamp_millis()
+    ));
+    std::fs::create_dir_all(&dir)?;
+    let file_name = path.file_name().context("无效的 cookie 路径")?;
+    let copy = dir.join(file_name);
+    std::fs::copy(path, &copy).with_context(|| format!("无法复制 {}", path.display()))?;
+    let wal = PathBuf::from(format!("{}-wal", path.display()));
+    if wal.is_file() {
+        std::fs::copy(&wal, format!("{}-wal", copy.display())).ok();
+    }
+    let conn = Connection::open_with_flags(&copy, OpenFlags::SQLITE_OPEN_READ_ONLY)?;
+    Ok((conn, dir))
+}
+
+fn host_clause(column: &str) -> String {
+    COOKIE_HOSTS
+        .iter()
+        .map(|h| format!("{column} = '{h}' OR {column} LIKE '%.{h}'"))
+        .collect::<Vec<_>>()
+        .join(" OR ")
+}
+
+/// 拼成 header 格式；同名 cookie 以最近访问的为准，`rows` 按访问时间升序
+fn join_cookies(rows: Vec<(String, String)>) -> String {
+    let mut cookies: Vec<(String, String)> = Vec::new();
+    for (name, value) in rows {
+        match cookies.iter_mut().find(|(k, _)| *k == name) {
+            Some(entry) => entry.1 = value,
+            None => cookies.push((name, value)),
+        }
+    }
+    cookies
+        .into_iter()
+        .map(|(k, v)| format!("{}={}", k, v))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// 不同容器（originAttributes）的 cookie 分属不同登录，不能按名字混在一起
+///
+/// 优先默认容器，其次最近访问过的容器，取第一组完整的登录；都不完整时返回排在最前的一组。
+fn read_firefox(conn: &Connection) -> Result<String> {
+    let sql = format!(
+        "SELECT originAttributes, name, value FROM moz_cookies WHERE {} ORDER BY lastAccessed",
+        host_clause("host")
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+        .collect::<rusqlite::Result<Vec<(String, String, String)>>>()?;
+    // (originAttributes, 最后一次访问的行号, 该容器的 cookie)
+    let mut groups: Vec<(String, usize, Vec<(String, String)>)> = Vec::new();
+    for (i, (origin, name, value)) in rows.into_iter().enumerate() {
+        match groups.iter_mut().find(|(o, _, _)| *o == origin) {
+            Some(group) => {
+                group.1 = i;
+                group.2.push((name, value));
+            }
+            None => groups.push((origin, i, vec![(name, value)])),
+        }
+    }
+    groups.sort_by_key(|(origin, last, _)| (!origin.is_empty(), std::cmp::Reverse(*last)));
+    let joined = groups
+        .into_iter()
+        .map(|(_, _, rows)| join_cookies(rows))
+        .collect::<Vec<_>>();
+    Ok(joined
+        .iter()
+        .find(|raw| AuthCookie::parse(raw).is_valid())
+        .or(joined.first())
+        .cloned()
+        .unwrap_or_default())
+}
+
+fn read_chromium(conn: &Connection) -> Result<String> {
+    let sql = format!(
+        "SELECT name, value, length(encrypted_value) FROM cookies WHERE {} ORDER BY last_access_utc",
+        host_clause("host_key")
+    );
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+    let encrypted = rows
+        .iter()
+        .filter(|(_, v, len)| v.is_empty() && *len > 0)
+        .count();
+    let plain = rows
+        .into_iter()
+        .filter(|(_, v, _)| !v.is_empty())
+        .map(|(k, v, _)| (k, v))
+        .collect::<Vec<_>>();
+    if plain.is_empty() && encrypted > 0 {
+        return Err(anyhow!("该配置的 cookie 已被系统密钥环加密，暂不支持读取"));
+    }
+    Ok(join_cookies(plain))
+}
+
+/// 读取配置中 x.com 的 cookie，拼成 header 格式
+fn read_cookies(profile: &BrowserProfile) -> Result<String> {
+    let (conn, dir) = open_copy(Path::new(&profile.cookies_path))?;
+    let raw = match profile.kind {
+        BrowserKind::Firefox => read_firefox(&conn),
+        BrowserKind::Chromium => read_chromium(&conn),
+    };
+    drop(conn);
+    std::fs::remove_dir_all(&dir).ok();
+    raw
+}
+
+#[tauri::command]
+#[specta::specta]
+pub async fn list_browser_profiles() -> Result<Vec<BrowserProfile>, String> {
+    if !cfg!(target_os = "linux") {
+        return Ok(Vec::new());
+    }
+    let home = home().ok_or_else(|| "找不到 HOME 目录".to_string())?;
+    let mut profiles = firefox_profiles(&home);
+    profiles.extend(chromium_profiles(&home));
+    Ok(profiles)
+}
+
+/// 从浏览器配置导入 cookie 并保存为账号
+#[tauri::command]
+#[specta::specta]
+pub async fn import_browser_cookies(profile: BrowserProfile) -> Result<XAccount, String> {
+    let raw = tauri::async_runtime::spawn_blocking(move || read_cookies(&profile))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())?;
+    let cookie = AuthCookie::parse(&raw);
+    if !cookie.is_valid() {
+        return Err("浏览器中没有有效的 x.com 登录，请先在浏览器中登录".to_string());
+    }
+    add_account(cookie.to_string()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_profiles_ini() {
+        let content = "[General]\nStartWithLastProfile=1\n\n[Profile0]\nName=default-release\nIsRelative=1\nPath=abcd.default-release\n\n[Profile1]\nName=work\nIsRelative=0\nPath=/data/ff/work\n\n[Install4F96D1932A9F858E]\nDefault=abcd.default-release\n";
+        let root = Path::new("/home/u/.mozilla/firefox");
+        let profiles = parse_profiles_ini(root, content);
+        assert_eq!(
+            profiles,
+            vec![
+                (
+                    "default-release".to_string(),
+                    root.join("abcd.default-release")
+                ),
+                ("work".to_string(), PathBuf::from("/data/ff/work")),
+            ]
+        );
+    }
+
+    fn moz_cookies(rows: &[(&str, &str, &str)]) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE moz_cookies (host TEXT, name TEXT, value TEXT, originAttributes TEXT, lastAccessed INTEGER);",
+        )
+        .unwrap();
+        for (i, (origin, name, value)) in rows.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO moz_cookies VALUES ('.x.com', ?1, ?2, ?3, ?4)",
+                rusqlite::params![name, value, origin, i as i64],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    #[test]
+    fn test_read_firefox_keeps_containers_apart() {
+        let work = "^userContextId=1";
+        let conn = moz_cookies(&[
+            ("", "twid", "u%3D1"),
+            (work, "twid", "u%3D2"),
+            ("", "ct0", "a"),
+            (work, "ct0", "b"),
+            ("", "kdt", "k1"),
+            (work, "kdt", "k2"),
+            (work, "auth_token", "t2"),
+            ("", "auth_token", "t1"),
+        ]);
+        let cookie = AuthCookie::parse(&read_firefox(&conn).unwrap());
+        assert_eq!(cookie.user_id(), Some(1));
+        assert_eq!(cookie.auth_token.as_deref(), Some("t1"));
+        assert_eq!(cookie.ct0.as_deref(), Some("a"));
+
+        // 默认容器没有登录时用容器里完整的那组
+        let conn = moz_cookies(&[
+            ("", "ct0", "a"),
+            (work, "twid", "u%3D2"),
+            (work, "ct0", "b"),
+            (work, "kdt", "k2"),
+            (work, "auth_token", "t2"),
+        ]);
+        let cookie = AuthCookie::parse(&read_firefox(&conn).unwrap());
+        assert_eq!(cookie.user_id(), Some(2));
+        assert_eq!(cookie.ct0.as_deref(), Some("b"));
+    }
+}
@@ -1,4 +1,6 @@
+pub mod account;
 pub mod auth;
+pub mod browser;
 pub mod enums;
 pub mod models;
 
@@ -50,18 +50,74 @@ impl AuthCookie {
                 .collect()
         };
 
-        // 提取所需的特定字段
+        let mut cookie = Self {
+            raw,
+            ct0: None,
+            twid: None,
+            auth_token: None,
+            kdt: None,
+        };
+        cookie.refresh_fields();
+        cookie
+    }
+
+    /// 从 raw 中提取所需的特定字段
+    fn refresh_fields(&mut self) {
         let find_value = |key: &str| -> Option<String> {
-            raw.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone())
+            self.raw
+                .iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.clone())
         };
+        self.ct0 = find_value("ct0");
+        self.twid = find_value("twid");
+        self.auth_token = find_value("auth_token");
+        self.kdt = find_value("kdt");
+    }
 
-        Self {
-            ct0: find_value("ct0"),
-            twid: find_value("twid"),
-            auth_token: find_value("auth_token"),
-            kdt: find_value("kdt"),
-            raw,
+    /// 合并响应头里的 `Set-Cookie`，过期或清空的 cookie 被移除；返回是否有变化
+    pub fn merge_set_cookies(&mut self, headers: &[(String, String)]) -> bool {
+        let mut changed = false;
+        let set_cookies = headers
+            .iter()
+            .filter(|(k, _)| k.eq_ignore_ascii_case("set-cookie"))
+            .map(|(_, v)| v.as_str());
+        for header in set_cookies {
+            let mut parts = header.split(';').map(str::trim);
+            let Some((name, value)) = parts.next().and_then(|p| p.split_once('=')) else {
+                continue;
+            };
+            let (name, value) = (name.trim(), value.trim());
+            let expired = value.is_empty()
+                || parts.any(|attr| {
+                    let Some((k, v)) = attr.split_once('=') else {
+                        return false;
+                    };
+                    match k.trim().to_ascii_lowercase().as_str() {
+                        "max-age" => v.trim().parse::<i64>().is_ok_and(|age| age <= 0),
+                        "expires" => {
+                            parse_expires(v.trim()).is_some_and(|t| t < chrono::Utc::now())
+                        }
+                        _ => false,
+                    }
+                });
+            let pos = self.raw.iter().position(|(k, _)| k == name);
+            match (pos, expired) {
+                (Some(i), true) => {
+                    self.raw.remove(i);
+                }
+                (Some(i), false) if self.raw[i].1 != value => {
+                    self.raw[i].1 = value.to_string();
+                }
+                (None, false) => self.raw.push((name.to_string(), value.to_string())),
+                _ => continue,
+            }
+            changed = true;
         }
+        if changed {
+            self.refresh_fields();
+        }
+        changed
     }
 
     /// 输出为标准 header 格式字符串
@@ -73,6 +129,16 @@ impl AuthCookie {
             .join("; ")
     }
 
+    /// 从 twid（形如 `u%3D123`）中取出账号的数字 id
+    pub fn user_id(&self) -> Option<i64> {
+        let twid = self.twid.as_deref()?;
+        twid.trim_matches('"')
+            .trim_start_matches("u%3D")
+            .trim_start_matches("u=")
+            .parse::<i64>()
+            .ok()
+    }
+
     /// 检查是否包含所有必需的字段
     pub fn is_valid(&self) -> bool {
         self.ct0.is_some() && self.twid.is_some() && self.auth_token.is_some() && self.kdt.is_some()
@@ -105,6 +171,17 @@ impl AuthCookie {
     }
 }
 
+/// `Expires` 除 RFC 2822 外常见 `Thu, 01-Jan-1970 00:00:01 GMT` 这种带连字符的写法
+fn parse_expires(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    if let Ok(t) = chrono::DateTime::parse_from_rfc2822(value) {
+        return Some(t.to_utc());
+    }
+    ["%a, %d-%b-%Y %H:%M:%S GMT", "%a, %d-%b-%y %H:%M:%S GMT"]
+        .iter()
+        .find_map(|fmt| chrono::NaiveDateTime::parse_from_str(value, fmt).ok())
+        .map(|t| t.and_utc())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,4 +207,35 @@ mod tests {
         assert_eq!(cookie.kdt, Some("abc".to_string()));
         assert!(cookie.is_valid());
     }
+
+    #[test]
+    fn test_user_id() {
+        let cookie = AuthCookie::parse("ct0=1; auth_token=2; twid=u%3D123456; kdt=3");
+        assert_eq!(cookie.user_id(), Some(123456));
+        assert_eq!(AuthCookie::parse("ct0=1; auth_token=2").user_id(), None);
+    }
+
+    #[test]
+    fn test_merge_set_cookies() {
+        let mut cookie =
+            AuthCookie::parse("ct0=old; auth_token=456; twid=u%3D789; kdt=abc; lang=en");
+        let headers = |values: &[&str]| {
+            values
+                .iter()
+                .map(|v| ("set-cookie".to_string(), v.to_string()))
+                .collect::<Vec<_>>()
+        };
+        let changed = cookie.merge_set_cookies(&headers(&[
+            "ct0=new; Max-Age=21600; Expires=Tue, 20 Oct 2099 00:00:00 GMT; Path=/; Domain=.x.com; Secure",
+            "lang=; Max-Age=0; Path=/",
+            "guest_id=v1%3A1; Path=/",
+            "auth_token=456; Expires=Thu, 01-Jan-1970 00:00:01 GMT; Path=/",
+        ]));
+        assert!(changed);
+        assert_eq!(cookie.auth_token, None);
+        assert_eq!(cookie.ct0, Some("new".to_string()));
+        assert!(!cookie.raw.iter().any(|(k, _)| k == "lang"));
+        assert!(cookie.raw.iter().any(|(k, _)| k == "guest_id"));
+        assert!(!cookie.merge_set_cookies(&headers(&["ct0=new; Path=/"])));
+    }
 }
@@ -40,6 +40,18 @@ impl AuthCredential {
         })
     }
 
+    /// 吸收响应中轮换的 cookie，ct0 变化时随之更新 CSRF 头；返回是否有变化
+    pub fn apply_set_cookies(&mut self, headers: &[(String, String)]) -> bool {
+        let Some(cookie) = self.cookie.as_mut() else {
+            return false;
+        };
+        if !cookie.merge_set_cookies(headers) {
+            return false;
+        }
+        self.csrf_token = cookie.ct0.clone();
+        true
+    }
+
     /// 转换为 HTTP 头
     pub fn headers(&self) -> HeaderMap {
         let mut headers = HeaderMap::new();
@@ -12,21 +12,37 @@ use domain::models::twitter::utils::clean_database;
 use domain::models::twitter::{
     content_to_copy::ContentToCopy,
     like::{take_single_like, LikedPost},
+    list::all_list_sources,
+    upstream::filter_checked_posts,
 };
 use domain::models::userkv::{get_userkv_value, upsert_userkv};
 use domain::models::{collect, meta};
 use domain::models::{interface, scroll_position};
+use domain::platform::api::bookmark::{self, ScanBookmarksEvent};
+use domain::platform::api::conversation;
+use domain::platform::api::credentials::{self, AuthStateEvent};
+use domain::platform::api::follows::{self, SnapshotFollowsEvent};
+use domain::platform::api::list::{self, ScanListEvent};
+use domain::platform::api::recheck::{self, RecheckPostsEvent};
+use domain::platform::api::search::{self, ScanSearchEvent};
 use domain::platform::api::user::ScanLikesEvent;
+use domain::platform::api::user_timeline::{self, ScanUserTimelineEvent};
 use domain::platform::emitter::AssetDownloadBatchEvent;
 use domain::platform::job::{self, Job};
 use domain::platform::scheduler::{self, Scheduler};
+use domain::platform::sink::{EventSink, TauriSink};
+use domain::platform::twitter::api::core::{registry, transport};
 use domain::platform::twitter::api::user;
-use domain::platform::{handle_entities_replace, Task, TaskKind};
+use domain::platform::twitter::auth::{account, browser};
+use domain::platform::{
+    handle_entities_replace, pause, proxy, recovery, stats, task, Task, TaskKind,
+};
 use serde::Serialize;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::str::FromStr;
 use std::sync::atomic::Ordering;
+use std::sync::Arc;
 use std::time::Duration;
 use surrealdb::RecordId;
 
@@ -56,16 +72,28 @@ thread_local! {
 pub fn run() {
     let events = collect_events![
         ScanLikesEvent,
+        ScanBookmarksEvent,
+        ScanUserTimelineEvent,
+        ScanListEvent,
+        ScanSearchEvent,
+        SnapshotFollowsEvent,
+        RecheckPostsEvent,
+        AuthStateEvent,
         AssetDownloadBatchEvent,
         event::ImportEvent,
         event::FullScreenEvent,
         scheduler::JobChecksEvent,
         scheduler::SchedulerPauseEvent,
+        scheduler::SchedulerRateLimitEvent,
+        stats::SchedulerStatsEvent,
         job::ScanLikesIncEvent,
     ];
 
     let commands = collect_commands![
         interface::take_post_chunk,
+        interface::take_bookmark_chunk,
+        interface::take_list_chunk,
+        interface::take_search_chunk,
         interface::check_has_data,
         copy_to_clipboard,
         meta::upsert_metakv,
@@ -76,12 +104,51 @@ pub fn run() {
         save_all,
         import_data,
         user::scan_likes_timeline,
+        bookmark::scan_bookmarks_timeline,
+        user_timeline::scan_user_timeline,
+        conversation::fetch_post_conversation,
+        conversation::take_post_conversations,
+        list::scan_list_timeline,
+        all_list_sources,
+        search::create_saved_search,
+        search::update_saved_search,
+        search::delete_saved_search,
+        search::all_saved_searches,
+        search::run_saved_search,
+        follows::snapshot_follows,
+        follows::follow_snapshots,
+        follows::diff_follow_snapshots,
+        follows::delete_follow_snapshot,
+        recheck::recheck_posts,
+        registry::get_graphql_overrides,
+        registry::set_graphql_overrides,
+        registry::reload_graphql_overrides,
+        transport::get_transport_mode,
+        transport::switch_transport_mode,
+        proxy::get_proxy_settings,
+        proxy::set_proxy_settings,
+        account::all_accounts,
+        account::add_account,
+        account::remove_account,
+        account::set_default_account,
+        credentials::verify_credentials,
+        browser::list_browser_profiles,
+        browser::import_browser_cookies,
+        filter_checked_posts,
         meta::get_save_dir,
         file::exists,
         app_ready,
         scheduler::reply_pending_jobs,
         scheduler::pause_scheduler,
         scheduler::resume_scheduler,
+        scheduler::paused_scopes,
+        scheduler::cancel_job,
+        scheduler::cancel_task,
+        stats::scheduler_stats,
+        task::list_failed_tasks,
+        task::retry_failed_tasks,
+        task::retry_all_failed_tasks,
+        task::discard_failed_tasks,
         get_mouse_and_window_position,
         collect::create_collection,
         collect::collect_post,
@@ -208,16 +275,39 @@ pub fn run() {
                         }
                     }
                     async_runtime::spawn(async move {
-                        Scheduler::<Task>::init(handle.clone()).await?;
-                        Scheduler::<Job>::init(handle.clone()).await?;
+                        if let Err(e) = recovery::recover().await {
+                            tracing::error!("启动恢复失败: {}", e);
+                        }
+                        if let Err(e) = pause::init().await {
+                            tracing::error!("加载暂停状态失败: {}", e);
+                        }
+                        if let Err(e) = registry::init().await {
+                            tracing::error!("加载 GraphQL 覆盖失败: {}", e);
+                        }
+                        if let Err(e) = proxy::init().await {
+                            tracing::error!("加载代理设置失败: {}", e);
+                        }
+                        if let Err(e) = account::init().await {
+                            tracing::error!("迁移账号归属失败: {}", e);
+                        }
+                        let sink: Arc<dyn EventSink> = Arc::new(TauriSink::new(handle.clone()));
+                        Scheduler::<Task>::init(sink.clone()).await?;
+                        Scheduler::<Job>::init(sink).await?;
+                        stats::spawn_stats_loop();
+                        search::spawn_search_schedule();
                         Ok::<(), anyhow::Error>(())
                     });
                     Ok(())
                 })
             })
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                block_on(scheduler::shutdown_all(Duration::from_secs(3)));
+            }
+        });
 }
 
 #[tauri::command]
@@ -65,7 +65,15 @@ pub fn read_tweets_from_json(file_path: PathBuf) -> Result<TweetData> {
 fn des_likedpost(json: &Value) -> Option<LikedPost> {
     let sortidx = json.pointer("/sortidx")?.as_str()?.parse::<u32>().unwrap();
     let post = json.pointer("/post").and_then(des_post)?;
-    Some(LikedPost { sortidx, post })
+    let account = json
+        .pointer("/account")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    Some(LikedPost {
+        sortidx,
+        post,
+        account,
+    })
 }
 
 fn des_post(json: &Value) -> Option<Post> {
@@ -299,6 +307,11 @@ fn des_card(json: &Value) -> Option<Card> {
             .and_then(|v| v.as_str())
             .map(|v| v.to_string()),
         url: json.pointer("/url")?.as_str()?.to_string(),
+        space_id: json
+            .pointer("/space_id")
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string()),
+        space: None,
     })
 }
 
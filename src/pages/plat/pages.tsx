@@ -215,7 +215,7 @@ function PlatTool() {
       <MenuItem
         name="Scan"
         fn={() => {
-          crab.scanLikesTimeline();
+          crab.scanLikesTimeline(null);
         }}
         icon={<icons.scan />}
       />
@@ -75,7 +75,7 @@ export default function Posts({ initialCursor = null }: PostsProps) {
 
   const loadMorePosts = async () => {
     if (!catPage) {
-      const result = await crab.takePostChunk(cursor);
+      const result = await crab.takePostChunk(cursor, null);
       result.tap(({ data, cursor: newCursor }) => {
         // const sortedData = data.sort((a, b) => b.sortidx - a.sortidx);
         data.forEach(({ post, sortidx }) => {
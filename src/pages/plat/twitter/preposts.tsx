@@ -130,7 +130,7 @@ export function PrePosts() {
                 icon={<icons.scan />}
                 content="Scan your liked posts"
                 onClick={async () => {
-                  crab.scanLikesTimeline();
+                  crab.scanLikesTimeline(null);
                   setScanning(true);
                   setIsLoading(true);
                 }}
@@ -83,9 +83,16 @@ export const LeftControls = memo(function LeftControlsComponent() {
 const SchedulerBadge = ({ count }: { count: number }) => {
   const [schedulerPause, setSchedulerPause] = station.schedulerPause.useAll();
   const [isHovered, setIsHovered] = useState(false);
+  useEffect(() => {
+    crab.pausedScopes().then((r) =>
+      r.tap((scopes) => setSchedulerPause(scopes.includes("Tasks"))),
+    );
+  }, []);
   useEffect(() => {
     const pause = events.schedulerPauseEvent.listen((event) => {
-      setSchedulerPause(event.payload.paused);
+      if (event.payload.scope === "Tasks") {
+        setSchedulerPause(event.payload.paused);
+      }
     });
     return () => {
       pause.then((f) => f());
@@ -105,9 +112,9 @@ const SchedulerBadge = ({ count }: { count: number }) => {
       onMouseLeave={() => setIsHovered(false)}
       onClick={() => {
         if (schedulerPause) {
-          crab.resumeScheduler();
+          crab.resumeScheduler("Tasks");
         } else {
-          crab.pauseScheduler();
+          crab.pauseScheduler("Tasks");
         }
       }}
     >